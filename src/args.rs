@@ -0,0 +1,158 @@
+use cfg_if::cfg_if;
+
+/// command line arguments like `cave --seed 42`, on the web they come from the page url
+/// instead, like `index.html?cave&seed=42`
+#[derive(Debug, Default)]
+pub struct Args {
+    /// everything that is not a flag, in order
+    pub positional: Vec<String>,
+    pub seed: Option<u64>,
+    /// maze size in tiles, like `--size 200x50`
+    pub size: Option<(usize, usize)>,
+    /// maze file to start with instead of generating one
+    pub load: Option<String>,
+    /// where to save the maze, defaults to `maze.txt`
+    pub save: Option<String>,
+    /// colors to read .ppm and .pgm mazes with, see [`crate::netpbm::Palette::parse`]
+    pub palette: Option<String>,
+    /// where to save the screen as a .ppm image, defaults to `maze.ppm`
+    pub export: Option<String>,
+    /// where to save the maze and search as an .svg picture, defaults to `maze.svg`
+    pub svg: Option<String>,
+    /// scene file to start with, see [`crate::scene`]
+    pub scene: Option<String>,
+    /// where to save the scene, defaults to `scene.ron`
+    pub save_scene: Option<String>,
+    /// where to save a trace of the search when it is done
+    pub record: Option<String>,
+    /// trace to play back instead of searching, see [`crate::trace`]
+    pub replay: Option<String>,
+    /// which scenario to show when loading a .scen file, counting from 1, defaults to the last
+    pub scenario: Option<usize>,
+    /// .scen file to check every search against, without opening a window
+    pub bench: Option<String>,
+    /// search steps per second, like `--rate 0.5` or `--rate 5000`
+    pub rate: Option<f64>,
+    /// algorithms to race side by side on the same maze, like `--race bfs,astar`
+    pub race: Vec<String>,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                let search = web_sys::window()
+                    .and_then(|win| win.location().search().ok())
+                    .unwrap_or_default();
+                // `?cave&seed=42` is the same as `cave --seed 42`
+                let words = search
+                    .trim_start_matches('?')
+                    .split('&')
+                    .filter(|v| !v.is_empty())
+                    .flat_map(|v| match v.split_once('=') {
+                        Some((key, value)) => vec![format!("--{key}"), value.to_string()],
+                        None => vec![v.to_string()],
+                    })
+                    .collect();
+            } else {
+                let words = std::env::args().skip(1).collect();
+            }
+        }
+        Self::from_words(words)
+    }
+
+    fn from_words(words: Vec<String>) -> Result<Self, String> {
+        let mut args = Self::default();
+        let mut words = words.into_iter();
+        while let Some(word) = words.next() {
+            match word.as_str() {
+                "--seed" => {
+                    let value = words.next().ok_or("--seed needs a number after it")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|e| format!("seed '{value}' is not a number: {e}"))?;
+                    args.seed = Some(seed);
+                }
+                "--size" => {
+                    let value = words
+                        .next()
+                        .ok_or("--size needs a size after it, like 200x50")?;
+                    let size = value
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .ok_or(format!("size '{value}' is not a size, like 200x50"))?;
+                    // the same sizes the - and + keys go between
+                    crate::check_size(size).map_err(|e| format!("size '{value}': {e}"))?;
+                    args.size = Some(size);
+                }
+                "--load" => {
+                    args.load = Some(words.next().ok_or("--load needs a file after it")?);
+                }
+                "--save" => {
+                    args.save = Some(words.next().ok_or("--save needs a file after it")?);
+                }
+                "--palette" => {
+                    args.palette = Some(words.next().ok_or("--palette needs a file after it")?);
+                }
+                "--export" => {
+                    args.export = Some(words.next().ok_or("--export needs a file after it")?);
+                }
+                "--svg" => {
+                    args.svg = Some(words.next().ok_or("--svg needs a file after it")?);
+                }
+                "--scene" => {
+                    args.scene = Some(words.next().ok_or("--scene needs a file after it")?);
+                }
+                "--save-scene" => {
+                    args.save_scene =
+                        Some(words.next().ok_or("--save-scene needs a file after it")?);
+                }
+                "--record" => {
+                    args.record = Some(words.next().ok_or("--record needs a file after it")?);
+                }
+                "--replay" => {
+                    args.replay = Some(words.next().ok_or("--replay needs a file after it")?);
+                }
+                "--scenario" => {
+                    let value = words.next().ok_or("--scenario needs a number after it")?;
+                    let scenario = value
+                        .parse()
+                        .ok()
+                        .filter(|&v| 0 < v)
+                        .ok_or(format!("scenario '{value}' is not a number from 1 up"))?;
+                    args.scenario = Some(scenario);
+                }
+                "--bench" => {
+                    args.bench = Some(words.next().ok_or("--bench needs a .scen file after it")?);
+                }
+                "--rate" => {
+                    let value = words.next().ok_or("--rate needs a number after it")?;
+                    let rate = value
+                        .parse()
+                        .ok()
+                        .filter(|&v: &f64| 0.0 < v)
+                        .ok_or(format!("rate '{value}' is not a number above 0"))?;
+                    args.rate = Some(rate);
+                }
+                "--race" => {
+                    let value = words
+                        .next()
+                        .ok_or("--race needs algorithms after it, like bfs,astar")?;
+                    args.race = value
+                        .split(',')
+                        .filter(|v| !v.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown flag '{flag}'")),
+                _ => args.positional.push(word),
+            }
+        }
+        Ok(args)
+    }
+
+    /// positional argument `i`, if it was given
+    pub fn get(&self, i: usize) -> Option<&str> {
+        self.positional.get(i).map(|v| v.as_str())
+    }
+}
@@ -0,0 +1,67 @@
+// a camera over the tile grid, so single tiles of a big maze can be looked at up close
+//
+// positions are in tiles, where (0, 0) is the top left corner of the grid and y grows
+// downwards, like rows. at zoom 1 the whole grid fits in the viewport, inside the margin
+use crate::graphics::Uniform;
+
+const ZOOM_MIN: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// the tile position in the middle of the viewport
+    pub center: [f32; 2],
+    pub zoom: f32,
+}
+
+impl Camera {
+    /// the whole grid, in the middle
+    pub fn fit(u: &Uniform) -> Self {
+        Self {
+            center: [u.tiles_x as f32 * 0.5, u.tiles_y as f32 * 0.5],
+            zoom: 1.0,
+        }
+    }
+
+    /// pixels per tile
+    pub fn scale(&self, u: &Uniform) -> f32 {
+        let tiles = [u.tiles_x as f32, u.tiles_y as f32];
+        let fit = [0, 1].map(|i| u.screen[i] * (1.0 - 2.0 * u.margin) / tiles[i]);
+        fit[0].min(fit[1]) * self.zoom
+    }
+
+    /// from tiles to clip space, column by column like wgsl wants it
+    pub fn view_proj(&self, u: &Uniform) -> [[f32; 4]; 4] {
+        let scale = self.scale(u);
+        let s = [0, 1].map(|i| 2.0 * scale / u.screen[i]);
+        // y flips, since clip space grows upwards
+        [
+            [s[0], 0.0, 0.0, 0.0],
+            [0.0, -s[1], 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-self.center[0] * s[0], self.center[1] * s[1], 0.0, 1.0],
+        ]
+    }
+
+    /// the tile position under a pixel of the viewport
+    pub fn tile_at(&self, u: &Uniform, pixel: [f32; 2]) -> [f32; 2] {
+        let scale = self.scale(u);
+        [0, 1].map(|i| self.center[i] + (pixel[i] - u.screen[i] * 0.5) / scale)
+    }
+
+    /// zooms by `factor`, and keeps what is under `pixel` where it is
+    pub fn zoom_at(&mut self, u: &Uniform, pixel: [f32; 2], factor: f32) {
+        let before = self.tile_at(u, pixel);
+        // at most half a viewport for one tile
+        let fit = Self::fit(u).scale(u);
+        let most = (u.screen[0].min(u.screen[1]) * 0.5 / fit).max(1.0);
+        self.zoom = (self.zoom * factor).clamp(ZOOM_MIN, most);
+        let after = self.tile_at(u, pixel);
+        self.center = [0, 1].map(|i| self.center[i] + before[i] - after[i]);
+    }
+
+    /// moves what is on screen by some pixels
+    pub fn pan(&mut self, u: &Uniform, pixels: [f32; 2]) {
+        let scale = self.scale(u);
+        self.center = [0, 1].map(|i| self.center[i] - pixels[i] / scale);
+    }
+}
@@ -0,0 +1,581 @@
+use crate::maze::*;
+use rand::Rng;
+use std::collections::VecDeque;
+
+const NEIGHBORS_4: [(isize, isize); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+const NEIGHBORS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// `walls` walls scattered at random, home and goal are not guaranteed to have a path between them
+///
+/// home and goal are near the top left, or as near as a small maze lets them be
+pub fn random<R: Rng>(w: usize, h: usize, walls: usize, rng: &mut R) -> Result<Grid, String> {
+    if w * h < 2 {
+        return Err(format!("a {w}x{h} maze has no room for both home and goal"));
+    }
+    let mut maze = Grid::new(w, h);
+    let home = (2.min(w - 1), 12.min(h - 1));
+    let mut goal = (10.min(w - 1), 12.min(h - 1));
+    if goal == home {
+        // the next tile, row by row
+        let i = (home.1 * w + home.0 + 1) % (w * h);
+        goal = (i % w, i / w);
+    }
+    maze.set(home.0, home.1, Room::Home(0))?;
+    maze.set(goal.0, goal.1, Room::Goal(0))?;
+
+    for _ in 0..walls {
+        let (x, y) = (rng.gen_range(0..w), rng.gen_range(0..h));
+        if maze.room(x, y) == Some(Room::Empty) {
+            maze.set(x, y, Room::Wall)?;
+        }
+    }
+
+    Ok(maze)
+}
+
+/// settings for [`cave`]
+#[derive(Debug, Clone)]
+pub struct CaveConfig {
+    /// chance for each tile to start out as a wall
+    pub fill: f64,
+    /// how many smoothing passes to run over the noise
+    pub passes: usize,
+    /// an empty tile turns into a wall if it has at least this many wall neighbors
+    pub birth: usize,
+    /// a wall stays a wall if it has at least this many wall neighbors
+    pub survival: usize,
+}
+
+impl Default for CaveConfig {
+    fn default() -> Self {
+        Self {
+            fill: 0.45,
+            passes: 5,
+            birth: 5,
+            survival: 4,
+        }
+    }
+}
+
+/// cellular automata cave: random noise smoothed by birth/survival passes over the 8 neighbors,
+/// out of bounds counts as wall
+///
+/// only the largest connected region is kept, see [`from_largest_region`]
+pub fn cave<R: Rng>(w: usize, h: usize, config: &CaveConfig, rng: &mut R) -> Result<Grid, String> {
+    let mut walls: Vec<bool> = (0..w * h).map(|_| rng.gen_bool(config.fill)).collect();
+
+    for _ in 0..config.passes {
+        let mut next = walls.clone();
+        for y in 0..h {
+            for x in 0..w {
+                let count = NEIGHBORS_8
+                    .iter()
+                    .filter(|(dx, dy)| is_wall(&walls, w, h, x as isize + dx, y as isize + dy))
+                    .count();
+                let i = y * w + x;
+                next[i] = if walls[i] {
+                    config.survival <= count
+                } else {
+                    config.birth <= count
+                };
+            }
+        }
+        walls = next;
+    }
+
+    // solid border, so the cave doesn't run into the edge of the screen
+    for y in 0..h {
+        for x in 0..w {
+            if x == 0 || y == 0 || x == w - 1 || y == h - 1 {
+                walls[y * w + x] = true;
+            }
+        }
+    }
+
+    let rooms = walls
+        .into_iter()
+        .map(|wall| if wall { Room::Wall } else { Room::Empty })
+        .collect();
+    from_largest_region(Grid::from_rooms(rooms, w, h)?, rng)
+}
+
+/// walls off everything but the largest connected region, and places home and goal inside it,
+/// so there is always a path between them. errors if the region is too small to fit both
+pub fn from_largest_region<R: Rng>(mut maze: Grid, rng: &mut R) -> Result<Grid, String> {
+    let (w, h) = (maze.w(), maze.h());
+    let walls: Vec<bool> = maze.iter().map(|(_, room)| room == Room::Wall).collect();
+    let region = largest_region(&walls, w, h);
+    if region.len() < 2 {
+        return Err(format!(
+            "largest open region has {} tiles, which is not enough for both home and goal",
+            region.len()
+        ));
+    }
+
+    let mut keep = vec![false; w * h];
+    for &(x, y) in region.iter() {
+        keep[y * w + x] = true;
+    }
+    for (i, keep) in keep.into_iter().enumerate() {
+        if !keep {
+            maze.set(i % w, i / w, Room::Wall)?;
+        }
+    }
+
+    // goal is the tile furthest away from home, so the search has somewhere to go
+    let home = region[rng.gen_range(0..region.len())];
+    let goal = furthest(&maze, home);
+    maze.set(home.0, home.1, Room::Home(0))?;
+    maze.set(goal.0, goal.1, Room::Goal(0))?;
+    Ok(maze)
+}
+
+/// perfect maze from a randomized depth first search, where there is exactly one path between
+/// any two tiles. corridors run along odd coordinates, with home in the top left corner
+pub fn perfect<R: Rng>(w: usize, h: usize, rng: &mut R) -> Result<Grid, String> {
+    if w < 3 || h < 3 {
+        return Err(format!(
+            "{w}x{h} is too small for a maze, it has to be at least 3x3"
+        ));
+    }
+
+    let mut maze = Grid::filled(w, h, Room::Wall);
+    maze.set(1, 1, Room::Empty)?;
+
+    let mut stack = vec![(1, 1)];
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited: Vec<(usize, usize)> = NEIGHBORS_4
+            .iter()
+            .map(|(dx, dy)| (x as isize + dx * 2, y as isize + dy * 2))
+            .filter(|&(nx, ny)| 0 < nx && 0 < ny && nx < w as isize - 1 && ny < h as isize - 1)
+            .map(|(nx, ny)| (nx as usize, ny as usize))
+            .filter(|&(nx, ny)| maze.room(nx, ny) == Some(Room::Wall))
+            .collect();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (nx, ny) = unvisited[rng.gen_range(0..unvisited.len())];
+        maze.set((x + nx) / 2, (y + ny) / 2, Room::Empty)?;
+        maze.set(nx, ny, Room::Empty)?;
+        stack.push((nx, ny));
+    }
+
+    let goal = furthest(&maze, (1, 1));
+    maze.set(1, 1, Room::Home(0))?;
+    maze.set(goal.0, goal.1, Room::Goal(0))?;
+    Ok(maze)
+}
+
+/// removes `fraction` of the dead ends by knocking out one of their walls, which adds loops,
+/// so there is more than one way to get anywhere. 0 keeps the maze as is, 1 removes every dead end
+///
+/// walls that join two dead ends are preferred, and the outer ring is never opened
+pub fn braid<R: Rng>(maze: &mut Grid, fraction: f64, rng: &mut R) {
+    use rand::seq::SliceRandom;
+
+    let (w, h) = (maze.w(), maze.h());
+    let open = |maze: &Grid, x: isize, y: isize| maze.get(x, y) != Room::Wall;
+    let is_dead_end = |maze: &Grid, x: usize, y: usize| {
+        open(maze, x as isize, y as isize)
+            && NEIGHBORS_4
+                .iter()
+                .filter(|(dx, dy)| open(maze, x as isize + dx, y as isize + dy))
+                .count()
+                == 1
+    };
+
+    let mut dead_ends: Vec<(usize, usize)> = (0..w * h)
+        .map(|i| (i % w, i / w))
+        .filter(|&(x, y)| is_dead_end(maze, x, y))
+        .collect();
+    dead_ends.shuffle(rng);
+    let count = (dead_ends.len() as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+
+    // one wall can open more than one dead end, so they are counted, not the walls
+    let target = dead_ends.len() - count;
+    let mut remaining = dead_ends.len();
+    for &(x, y) in &dead_ends {
+        if remaining <= target {
+            break;
+        }
+        // an earlier pass may have opened this one up already
+        if !is_dead_end(maze, x, y) {
+            continue;
+        }
+
+        // walls with an open tile behind them, that are not on the outer ring
+        let mut walls: Vec<((usize, usize), (usize, usize))> = NEIGHBORS_4
+            .iter()
+            .filter_map(|(dx, dy)| {
+                let (wx, wy) = (x as isize + dx, y as isize + dy);
+                let (bx, by) = (x as isize + dx * 2, y as isize + dy * 2);
+                let inside = 0 < wx && 0 < wy && wx < w as isize - 1 && wy < h as isize - 1;
+                (inside && !open(maze, wx, wy) && open(maze, bx, by))
+                    .then_some(((wx as usize, wy as usize), (bx as usize, by as usize)))
+            })
+            .collect();
+        walls.shuffle(rng);
+        walls.sort_by_key(|(_, behind)| !is_dead_end(maze, behind.0, behind.1));
+
+        if let Some(&((wx, wy), _)) = walls.first() {
+            // every dead end next to the wall gets a second way out
+            let opened = NEIGHBORS_4
+                .iter()
+                .map(|(dx, dy)| (wx as isize + dx, wy as isize + dy))
+                .filter(|&(nx, ny)| is_dead_end(maze, nx as usize, ny as usize))
+                .count();
+            maze.set(wx, wy, Room::Empty).unwrap();
+            remaining -= opened;
+        }
+    }
+}
+
+fn is_wall(walls: &[bool], w: usize, h: usize, x: isize, y: isize) -> bool {
+    if x < 0 || y < 0 || w as isize <= x || h as isize <= y {
+        return true;
+    }
+    walls[y as usize * w + x as usize]
+}
+
+/// every open tile in the biggest 4-connected region
+fn largest_region(walls: &[bool], w: usize, h: usize) -> Vec<(usize, usize)> {
+    let mut seen = vec![false; w * h];
+    let mut largest = Vec::new();
+
+    for start in 0..w * h {
+        if walls[start] || seen[start] {
+            continue;
+        }
+
+        seen[start] = true;
+        let mut region = Vec::new();
+        let mut queue = VecDeque::from([(start % w, start / w)]);
+        while let Some((x, y)) = queue.pop_front() {
+            region.push((x, y));
+            for (dx, dy) in NEIGHBORS_4 {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if is_wall(walls, w, h, nx, ny) {
+                    continue;
+                }
+                let i = ny as usize * w + nx as usize;
+                if !seen[i] {
+                    seen[i] = true;
+                    queue.push_back((nx as usize, ny as usize));
+                }
+            }
+        }
+
+        if largest.len() < region.len() {
+            largest = region;
+        }
+    }
+
+    largest
+}
+
+/// the last open tile a breadth first flood from `from` reaches
+fn furthest(maze: &Grid, from: (usize, usize)) -> (usize, usize) {
+    let mut seen = vec![false; maze.w() * maze.h()];
+    seen[from.1 * maze.w() + from.0] = true;
+    let mut last = from;
+    let mut queue = VecDeque::from([from]);
+    while let Some((x, y)) = queue.pop_front() {
+        last = (x, y);
+        for ((nx, ny), room) in maze.neighbors(x, y) {
+            let i = ny * maze.w() + nx;
+            if !seen[i] && room != Room::Wall {
+                seen[i] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    last
+}
+
+/// how [`dungeon`] joins rooms together
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Corridor {
+    /// one horizontal and one vertical run
+    LShaped,
+    /// shortest path where digging through wall costs extra, so existing floor gets reused
+    Carved,
+}
+
+/// settings for [`dungeon`]
+#[derive(Debug, Clone)]
+pub struct DungeonConfig {
+    /// regions smaller than this on both axes are not split further
+    pub leaf: usize,
+    /// smallest room side, walls not included
+    pub room_min: usize,
+    pub corridor: Corridor,
+    /// put a door where a corridor enters a room, `None` for open doorways
+    pub door_cost: Option<u16>,
+    /// terrain weight of corridor tiles, 1 is the same as plain floor
+    pub corridor_cost: u16,
+}
+
+impl Default for DungeonConfig {
+    fn default() -> Self {
+        Self {
+            leaf: 24,
+            room_min: 4,
+            corridor: Corridor::LShaped,
+            door_cost: Some(4),
+            corridor_cost: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+impl Rect {
+    fn center(&self) -> (usize, usize) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+
+    fn contains(&self, (x, y): (usize, usize)) -> bool {
+        self.x <= x && x < self.x + self.w && self.y <= y && y < self.y + self.h
+    }
+}
+
+/// roguelike dungeon: the area is split in two recursively (bsp), one room is placed in each leaf,
+/// and sibling subtrees are joined with corridors
+///
+/// home is placed in the first room and goal in the last. errors if the area can't fit two rooms
+pub fn dungeon<R: Rng>(
+    w: usize,
+    h: usize,
+    config: &DungeonConfig,
+    rng: &mut R,
+) -> Result<Grid, String> {
+    let mut maze = Grid::filled(w, h, Room::Wall);
+
+    // outer ring stays wall
+    let area = Rect {
+        x: 1,
+        y: 1,
+        w: w.saturating_sub(2),
+        h: h.saturating_sub(2),
+    };
+    let mut rooms = Vec::new();
+    split(&mut maze, area, config, rng, &mut rooms);
+
+    if rooms.len() < 2 {
+        return Err(format!(
+            "{w}x{h} only fits {} room(s) with leaf size {} and room size {}",
+            rooms.len(),
+            config.leaf,
+            config.room_min
+        ));
+    }
+
+    let home = rooms[0].center();
+    let goal = rooms[rooms.len() - 1].center();
+    maze.set(home.0, home.1, Room::Home(0))?;
+    maze.set(goal.0, goal.1, Room::Goal(0))?;
+    Ok(maze)
+}
+
+/// carves the rooms of `area` and returns the center of one of them, for connecting to its sibling
+fn split<R: Rng>(
+    maze: &mut Grid,
+    area: Rect,
+    config: &DungeonConfig,
+    rng: &mut R,
+    rooms: &mut Vec<Rect>,
+) -> Option<(usize, usize)> {
+    // a leaf needs room for the room itself and a wall on each side
+    let fits = config.room_min + 2;
+    let can_x = fits * 2 <= area.w && config.leaf <= area.w;
+    let can_y = fits * 2 <= area.h && config.leaf <= area.h;
+
+    let vertical = match (can_x, can_y) {
+        (false, false) => return leaf(maze, area, config, rng, rooms),
+        (true, false) => true,
+        (false, true) => false,
+        (true, true) => area.h < area.w || (area.w == area.h && rng.gen_bool(0.5)),
+    };
+
+    let (a, b) = if vertical {
+        let at = rng.gen_range(fits..=area.w - fits);
+        (
+            Rect { w: at, ..area },
+            Rect {
+                x: area.x + at,
+                w: area.w - at,
+                ..area
+            },
+        )
+    } else {
+        let at = rng.gen_range(fits..=area.h - fits);
+        (
+            Rect { h: at, ..area },
+            Rect {
+                y: area.y + at,
+                h: area.h - at,
+                ..area
+            },
+        )
+    };
+
+    let from = split(maze, a, config, rng, rooms);
+    let to = split(maze, b, config, rng, rooms);
+    match (from, to) {
+        (Some(from), Some(to)) => {
+            connect(maze, from, to, config, rng, rooms);
+            Some(if rng.gen_bool(0.5) { from } else { to })
+        }
+        (from, to) => from.or(to),
+    }
+}
+
+fn leaf<R: Rng>(
+    maze: &mut Grid,
+    area: Rect,
+    config: &DungeonConfig,
+    rng: &mut R,
+    rooms: &mut Vec<Rect>,
+) -> Option<(usize, usize)> {
+    if area.w < config.room_min + 2 || area.h < config.room_min + 2 {
+        return None;
+    }
+
+    let w = rng.gen_range(config.room_min..=area.w - 2);
+    let h = rng.gen_range(config.room_min..=area.h - 2);
+    let room = Rect {
+        x: area.x + rng.gen_range(1..=area.w - 1 - w),
+        y: area.y + rng.gen_range(1..=area.h - 1 - h),
+        w,
+        h,
+    };
+
+    for y in room.y..room.y + room.h {
+        for x in room.x..room.x + room.w {
+            maze.set(x, y, Room::Empty).unwrap();
+        }
+    }
+    rooms.push(room);
+    Some(room.center())
+}
+
+fn connect<R: Rng>(
+    maze: &mut Grid,
+    from: (usize, usize),
+    to: (usize, usize),
+    config: &DungeonConfig,
+    rng: &mut R,
+    rooms: &[Rect],
+) {
+    let path = match config.corridor {
+        Corridor::LShaped => {
+            let corner = if rng.gen_bool(0.5) {
+                (to.0, from.1)
+            } else {
+                (from.0, to.1)
+            };
+            let mut path = line(from, corner);
+            path.extend(line(corner, to).into_iter().skip(1));
+            path
+        }
+        Corridor::Carved => carve(maze, from, to),
+    };
+
+    let in_room = |p: (usize, usize)| rooms.iter().any(|r| r.contains(p));
+    let corridor = match config.corridor_cost {
+        1 => Room::Empty,
+        cost => Room::Terrain(cost),
+    };
+    for &p in path.iter() {
+        if !in_room(p) && maze.get(p.0 as isize, p.1 as isize) == Room::Wall {
+            maze.set(p.0, p.1, corridor).unwrap();
+        }
+    }
+
+    if let Some(cost) = config.door_cost {
+        for pair in path.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let door = match (in_room(a), in_room(b)) {
+                (true, false) => b,
+                (false, true) => a,
+                _ => continue,
+            };
+            maze.set(door.0, door.1, Room::Terrain(cost)).unwrap();
+        }
+    }
+}
+
+/// straight line between two points on the same row or column, both ends included
+fn line(from: (usize, usize), to: (usize, usize)) -> Vec<(usize, usize)> {
+    let step = |a: usize, b: usize| (b as isize - a as isize).signum();
+    let (dx, dy) = (step(from.0, to.0), step(from.1, to.1));
+    let mut p = from;
+    let mut out = vec![p];
+    while p != to {
+        p = ((p.0 as isize + dx) as usize, (p.1 as isize + dy) as usize);
+        out.push(p);
+    }
+    out
+}
+
+/// a* where walls cost more than floor, and the outer ring can't be dug through
+fn carve(maze: &Grid, from: (usize, usize), to: (usize, usize)) -> Vec<(usize, usize)> {
+    use std::cmp::Reverse;
+    use std::collections::{BinaryHeap, HashMap};
+
+    const WALL_COST: usize = 5;
+    let (w, h) = (maze.w(), maze.h());
+    let distance = |p: (usize, usize)| p.0.abs_diff(to.0) + p.1.abs_diff(to.1);
+
+    let mut parents = HashMap::new();
+    let mut costs = HashMap::from([(from, 0)]);
+    let mut open = BinaryHeap::from([Reverse((distance(from), from))]);
+    while let Some(Reverse((_, p))) = open.pop() {
+        if p == to {
+            break;
+        }
+        for (dx, dy) in NEIGHBORS_4 {
+            let (nx, ny) = (p.0 as isize + dx, p.1 as isize + dy);
+            if nx < 1 || ny < 1 || w as isize - 1 <= nx || h as isize - 1 <= ny {
+                continue;
+            }
+            let n = (nx as usize, ny as usize);
+            let step = match maze.get(nx, ny) {
+                Room::Wall => WALL_COST,
+                _ => 1,
+            };
+            let cost = costs[&p] + step;
+            if matches!(costs.get(&n), Some(&old) if old <= cost) {
+                continue;
+            }
+            costs.insert(n, cost);
+            parents.insert(n, p);
+            open.push(Reverse((cost + distance(n), n)));
+        }
+    }
+
+    let mut path = vec![to];
+    while let Some(&p) = parents.get(path.last().unwrap()) {
+        path.push(p);
+    }
+    path.reverse();
+    path
+}
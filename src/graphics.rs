@@ -1,5 +1,6 @@
 // todo: change fading to happen in compute shader
 // todo: add support for transparancy
+use crate::camera::Camera;
 use crate::color::Color;
 use bytemuck::bytes_of;
 use log::{debug, error, info, trace, warn};
@@ -56,6 +57,12 @@ pub struct InstanceStrength {
     value: f32,
 }
 
+impl Default for InstanceStrength {
+    fn default() -> Self {
+        Self { value: 0.0 }
+    }
+}
+
 impl InstanceStrength {
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         use std::mem;
@@ -87,6 +94,15 @@ pub struct InstanceColorRange {
     // speed: f32,
 }
 
+impl Default for InstanceColorRange {
+    fn default() -> Self {
+        Self {
+            high: [0.9, 0.9, 0.9],
+            low: [0.1, 0.1, 0.1],
+        }
+    }
+}
+
 impl InstanceColorRange {
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         use std::mem;
@@ -109,30 +125,268 @@ impl InstanceColorRange {
     }
 }
 
+/// one character of text, see text.wgsl
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Glyph {
+    position: [f32; 2],
+    scale: f32,
+    index: u32,
+    color: [f32; 3],
+}
+
+impl Glyph {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Glyph>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Uint32,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+/// draws the text queued with [`State::text`] over the tiles, in window pixels
+struct Text {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    /// the window size, and the size of a character in font pixels
+    screen_buffer: wgpu::Buffer,
+    /// cleared after every frame
+    glyphs: Vec<Glyph>,
+    glyph_buffer: wgpu::Buffer,
+    /// how many glyphs fit in the buffer, it is made bigger when more are queued
+    capacity: usize,
+}
+
+impl Text {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("text shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("text.wgsl"))),
+        });
+
+        let (pixels, w, h) = crate::font::atlas();
+        let atlas = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("font atlas"),
+                size: wgpu::Extent3d {
+                    width: w,
+                    height: h,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            &pixels,
+        );
+        let atlas_view = atlas.create_view(&wgpu::TextureViewDescriptor::default());
+        // nearest, so the pixels stay sharp
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+        let screen_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("text screen buffer"),
+            contents: bytemuck::cast_slice(&[0.0f32; 4]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("text bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("text bind group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: screen_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("text pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("text pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Glyph::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let capacity = 256;
+        Self {
+            pipeline,
+            bind_group,
+            screen_buffer,
+            glyphs: Vec::new(),
+            glyph_buffer: Self::glyph_buffer(device, capacity),
+            capacity,
+        }
+    }
+
+    fn glyph_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("glyph buffer"),
+            size: (capacity * std::mem::size_of::<Glyph>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+fn instance_buffer(device: &wgpu::Device, label: &str, contents: &[u8]) -> wgpu::Buffer {
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some(label),
+        contents,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+    })
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Uniform {
+    /// from tiles to clip space, see [`Camera::view_proj`]
+    pub view_proj: [[f32; 4]; 4],
     pub tiles_x: u32,
+    pub tiles_y: u32,
+    /// space between tiles, as a fraction of a tile
     pub gap: f32,
+    /// space around the grid, as a fraction of the screen on each side
     pub margin: f32,
     pub speed: f32,
     pub mouse_speed: f32,
-    // apparently uniforms requires 16 byte (4 float) spacing,
-    // so padding has to be this size, and this location pub _padding: f32,
-    _padding: f32,
     pub mouse: [f32; 2],
+    /// viewport size in pixels, so tiles stay square
+    pub screen: [f32; 2],
+    // apparently uniforms requires 16 byte (4 float) spacing,
+    // so padding has to be this size, and this location
+    _padding: [f32; 2],
 }
 
 impl Default for Uniform {
     fn default() -> Self {
         Self {
+            view_proj: [
+                [1., 0., 0., 0.],
+                [0., 1., 0., 0.],
+                [0., 0., 1., 0.],
+                [0., 0., 0., 1.],
+            ],
             tiles_x: 6,
+            tiles_y: 6,
             gap: 0.05,
             margin: 0.02,
             speed: 1.0,
             mouse_speed: 0.0,
             mouse: [0.; 2],
-            _padding: 0.0,
+            screen: [1.; 2],
+            _padding: [0.; 2],
+        }
+    }
+}
+
+/// the tiles of one viewport. every viewport shows the same grid, so only the colors differ
+struct Viewport {
+    instances_strength: Vec<InstanceStrength>,
+    instances_color_range: Vec<InstanceColorRange>,
+    instance_buffer_strength: wgpu::Buffer,
+    instance_buffer_color_range: wgpu::Buffer,
+}
+
+impl Viewport {
+    fn new(device: &wgpu::Device, instances: usize) -> Self {
+        let instances_strength = vec![InstanceStrength::default(); instances];
+        let instance_buffer_strength = instance_buffer(
+            device,
+            "instance buffer strenght",
+            bytemuck::cast_slice(&instances_strength),
+        );
+        let instances_color_range = vec![InstanceColorRange::default(); instances];
+        let instance_buffer_color_range = instance_buffer(
+            device,
+            "instance buffer color range",
+            bytemuck::cast_slice(&instances_color_range),
+        );
+        Self {
+            instances_strength,
+            instances_color_range,
+            instance_buffer_strength,
+            instance_buffer_color_range,
         }
     }
 }
@@ -151,17 +405,18 @@ pub struct State {
     pub size: winit::dpi::PhysicalSize<u32>,
     w: u32,
     h: u32,
-    instances_strength: Vec<InstanceStrength>,
-    instances_color_range: Vec<InstanceColorRange>,
-    instance_buffer_strength: wgpu::Buffer,
-    instance_buffer_color_range: wgpu::Buffer,
+    /// side by side, in rows when there are many
+    viewports: Vec<Viewport>,
     uniform: Uniform,
     uniform_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
+    camera: Camera,
+    text: Text,
 }
 
 impl State {
-    pub async fn new(window: Window) -> Self {
+    /// `w` and `h` is the size of the grid in tiles
+    pub async fn new(window: Window, w: u32, h: u32) -> Self {
         let size = window.inner_size();
 
         let instance = wgpu::Instance::default();
@@ -198,10 +453,11 @@ impl State {
             source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
         });
 
-        let (w, h) = (128, 128);
         let instances = w * h;
         let uniform = Uniform {
             tiles_x: w,
+            tiles_y: h,
+            screen: [size.width as f32, size.height as f32],
             ..Uniform::default()
         };
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -324,26 +580,8 @@ impl State {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
         });
 
-        let instances_strength = vec![InstanceStrength { value: 0.0 }; instances as usize];
-        let instance_buffer_strength =
-            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("instance buffer strenght"),
-                contents: bytemuck::cast_slice(&instances_strength),
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            });
-        let instances_color_range = vec![
-            InstanceColorRange {
-                high: [0.9, 0.9, 0.9],
-                low: [0.1, 0.1, 0.1],
-            };
-            instances as usize
-        ];
-        let instance_buffer_color_range =
-            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("instance buffer color range"),
-                contents: bytemuck::cast_slice(&instances_color_range),
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            });
+        let viewports = vec![Viewport::new(&device, instances as usize)];
+        let text = Text::new(&device, &queue, swapchain_format);
 
         Self {
             surface,
@@ -358,13 +596,12 @@ impl State {
             vertex_array,
             w,
             h,
-            instances_strength,
-            instances_color_range,
-            instance_buffer_strength,
-            instance_buffer_color_range,
+            viewports,
+            camera: Camera::fit(&uniform),
             uniform,
             uniform_buffer,
             bind_group,
+            text,
         }
     }
 
@@ -378,16 +615,86 @@ impl State {
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
+            self.layout();
         }
     }
 
+    /// columns and rows of viewports, as square as they can be
+    fn viewport_grid(&self) -> (u32, u32) {
+        let n = self.viewports.len() as f32;
+        let columns = n.sqrt().ceil();
+        (columns as u32, (n / columns).ceil() as u32)
+    }
+
+    /// where viewport `i` is in the window, in pixels, as x, y, width and height
+    pub fn viewport_rect(&self, i: usize) -> [f32; 4] {
+        let (columns, rows) = self.viewport_grid();
+        let (w, h) = (
+            self.size.width as f32 / columns as f32,
+            self.size.height as f32 / rows as f32,
+        );
+        let i = i as u32;
+        [(i % columns) as f32 * w, (i / columns) as f32 * h, w, h]
+    }
+
+    /// the shader lays out tiles in one viewport, and they are all the same size
+    fn layout(&mut self) {
+        let [_, _, w, h] = self.viewport_rect(0);
+        self.uniform.screen = [w, h];
+    }
+
+    /// how many copies of the grid to show, each with its own colors. every tile is cleared
+    pub fn set_viewports(&mut self, n: usize) {
+        let instances = (self.w * self.h) as usize;
+        self.viewports = (0..n.max(1))
+            .map(|_| Viewport::new(&self.device, instances))
+            .collect();
+        self.layout();
+    }
+
+    pub fn viewports(&self) -> usize {
+        self.viewports.len()
+    }
+
+    /// which viewport a pixel in the window is in
+    pub fn viewport_at(&self, x: f64, y: f64) -> usize {
+        let (columns, _) = self.viewport_grid();
+        let [_, _, w, h] = self.viewport_rect(0);
+        let (column, row) = ((x as f32 / w) as usize, (y as f32 / h) as usize);
+        (row * columns as usize + column).min(self.viewports.len() - 1)
+    }
+
+    /// a pixel in the window, from the corner of whichever viewport it is in
+    fn in_viewport(&self, x: f64, y: f64) -> [f32; 2] {
+        let [_, _, w, h] = self.viewport_rect(0);
+        [x as f32 % w, y as f32 % h]
+    }
+
+    /// zooms every viewport by `factor`, towards a pixel in the window
+    pub fn zoom(&mut self, x: f64, y: f64, factor: f32) {
+        let pixel = self.in_viewport(x, y);
+        self.camera.zoom_at(&self.uniform, pixel, factor);
+    }
+
+    /// moves every viewport by some pixels
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.camera.pan(&self.uniform, [dx as f32, dy as f32]);
+    }
+
+    /// shows the whole grid again
+    pub fn fit_camera(&mut self) {
+        self.camera = Camera::fit(&self.uniform);
+    }
+
     pub fn input(&mut self, event: &WindowEvent) -> bool {
         false
     }
 
     pub fn update(&mut self) {
-        for tile in self.instances_strength.iter_mut() {
-            tile.value += 0.01;
+        for viewport in self.viewports.iter_mut() {
+            for tile in viewport.instances_strength.iter_mut() {
+                tile.value += 0.01;
+            }
         }
     }
 
@@ -414,12 +721,52 @@ impl State {
         // for (i, v) in self.instances_strength.iter_mut().enumerate() {
         //     v.value -= 0.1 * i as f32 / size as f32;
         // }
-        for tile in self.instances_strength.iter_mut() {
-            tile.value -= with;
+        for viewport in self.viewports.iter_mut() {
+            for tile in viewport.instances_strength.iter_mut() {
+                tile.value -= with;
+            }
         }
     }
 
+    /// pixels per font pixel, 2 or more on screens with small pixels
+    fn text_scale(&self) -> f32 {
+        (2.0 * self.window.scale_factor() as f32).round().max(1.0)
+    }
+
+    /// width and height in pixels of one character, and of one line
+    pub fn text_cell(&self) -> [f32; 2] {
+        let scale = self.text_scale();
+        [
+            crate::font::CELL.0 as f32 * scale,
+            crate::font::CELL.1 as f32 * scale,
+        ]
+    }
+
+    /// queues a line of text for the next frame, from its top left corner in window pixels. it
+    /// is drawn over everything, on black
+    pub fn text(&mut self, x: f32, y: f32, text: &str, color: Color) {
+        let scale = self.text_scale();
+        let [w, _] = self.text_cell();
+        let color = color.into();
+        // whole pixels, so every font pixel covers the same screen pixels
+        let (x, y) = (x.round(), y.round());
+        self.text
+            .glyphs
+            .extend(text.chars().enumerate().map(|(i, c)| Glyph {
+                position: [x + i as f32 * w, y],
+                scale,
+                index: crate::font::glyph(c),
+                color,
+            }));
+    }
+
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        // the text is for this frame only, even when it can't be drawn
+        let glyphs = std::mem::take(&mut self.text.glyphs);
+        if self.text.capacity < glyphs.len() {
+            self.text.capacity = glyphs.len().next_power_of_two();
+            self.text.glyph_buffer = Text::glyph_buffer(&self.device, self.text.capacity);
+        }
         let frame = self.surface.get_current_texture()?;
         let view = frame
             .texture
@@ -442,10 +789,21 @@ impl State {
             });
             rpass.set_pipeline(&self.render_pipeline);
             rpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            rpass.set_vertex_buffer(1, self.instance_buffer_strength.slice(..));
-            rpass.set_vertex_buffer(2, self.instance_buffer_color_range.slice(..));
             rpass.set_bind_group(0, &self.bind_group, &[]);
-            rpass.draw(0..self.num_vertices, 0..(self.w * self.h));
+            for (i, viewport) in self.viewports.iter().enumerate() {
+                let [x, y, w, h] = self.viewport_rect(i);
+                rpass.set_viewport(x, y, w, h, 0.0, 1.0);
+                rpass.set_vertex_buffer(1, viewport.instance_buffer_strength.slice(..));
+                rpass.set_vertex_buffer(2, viewport.instance_buffer_color_range.slice(..));
+                rpass.draw(0..self.num_vertices, 0..(self.w * self.h));
+            }
+
+            let (w, h) = (self.size.width as f32, self.size.height as f32);
+            rpass.set_viewport(0.0, 0.0, w, h, 0.0, 1.0);
+            rpass.set_pipeline(&self.text.pipeline);
+            rpass.set_bind_group(0, &self.text.bind_group, &[]);
+            rpass.set_vertex_buffer(0, self.text.glyph_buffer.slice(..));
+            rpass.draw(0..6, 0..glyphs.len() as u32);
         }
 
         self.queue.write_buffer(
@@ -453,38 +811,130 @@ impl State {
             0,
             bytemuck::cast_slice(&self.vertex_array),
         );
-        self.queue.write_buffer(
-            &self.instance_buffer_strength,
-            0,
-            bytemuck::cast_slice(&self.instances_strength),
-        );
+        self.uniform.view_proj = self.camera.view_proj(&self.uniform);
         self.queue.write_buffer(
             &self.uniform_buffer,
             0,
             bytemuck::cast_slice(&[self.uniform]),
         );
-        self.queue.write_buffer(
-            &self.instance_buffer_color_range,
-            0,
-            bytemuck::cast_slice(&self.instances_color_range),
-        );
+        for viewport in &self.viewports {
+            self.queue.write_buffer(
+                &viewport.instance_buffer_strength,
+                0,
+                bytemuck::cast_slice(&viewport.instances_strength),
+            );
+            self.queue.write_buffer(
+                &viewport.instance_buffer_color_range,
+                0,
+                bytemuck::cast_slice(&viewport.instances_color_range),
+            );
+        }
+        let (cell_w, cell_h) = crate::font::CELL;
+        let screen = [
+            self.size.width as f32,
+            self.size.height as f32,
+            cell_w as f32,
+            cell_h as f32,
+        ];
+        self.queue
+            .write_buffer(&self.text.screen_buffer, 0, bytemuck::cast_slice(&screen));
+        self.queue
+            .write_buffer(&self.text.glyph_buffer, 0, bytemuck::cast_slice(&glyphs));
         self.queue.submit(Some(encoder.finish()));
         frame.present();
         Ok(())
     }
 
-    /// panics if tile is out of bounds
+    /// paints a tile in every viewport, panics if tile is out of bounds
     pub fn paint(&mut self, tile: Tile) {
-        if self.w <= tile.x || self.h <= tile.y {
+        let (x, y) = (tile.x, tile.y);
+        let (high, low) = (tile.high.into(), tile.low.into());
+        for i in 0..self.viewports.len() {
+            self.paint_colors(i, x, y, high, low);
+        }
+    }
+
+    /// paints a tile in one viewport, panics if tile or viewport is out of bounds
+    pub fn paint_in(&mut self, viewport: usize, tile: Tile) {
+        self.paint_colors(viewport, tile.x, tile.y, tile.high.into(), tile.low.into());
+    }
+
+    fn paint_colors(&mut self, viewport: usize, x: u32, y: u32, high: [f32; 3], low: [f32; 3]) {
+        if self.w <= x || self.h <= y {
             // dbg!(self.uniform.tiles_x, self.instances_color_range.len());
-            panic!("tile provided was out of bounds, \n\twidth: {}, tile.x: {}\n\theight: {}, tile.y: {}", self.w, tile.x, self.h, tile.y);
+            panic!("tile provided was out of bounds, \n\twidth: {}, tile.x: {}\n\theight: {}, tile.y: {}", self.w, x, self.h, y);
+        }
+
+        let index = (x + y * self.w) as usize;
+        let viewport = &mut self.viewports[viewport];
+        let mut i: &mut InstanceColorRange = &mut viewport.instances_color_range[index];
+        i.high = high;
+        i.low = low;
+        viewport.instances_strength[index].value = 0.0;
+    }
+
+    /// sets one tile back to how it looked at the start, panics if out of bounds
+    pub fn unpaint(&mut self, x: u32, y: u32) {
+        if self.w <= x || self.h <= y {
+            panic!(
+                "tile to unpaint was out of bounds, \n\twidth: {}, x: {}\n\theight: {}, y: {}",
+                self.w, x, self.h, y
+            );
+        }
+        let index = (x + y * self.w) as usize;
+        for viewport in self.viewports.iter_mut() {
+            viewport.instances_color_range[index] = InstanceColorRange::default();
+            viewport.instances_strength[index] = InstanceStrength::default();
         }
+    }
+
+    /// the tile under a pixel in the window, in whichever viewport it is, through the camera.
+    /// `None` outside the grid, and in the gap between tiles
+    pub fn pick(&self, x: f64, y: f64) -> Option<(u32, u32)> {
+        let u = &self.uniform;
+        let tiles = [u.tiles_x as f32, u.tiles_y as f32];
+        let t = self.camera.tile_at(u, self.in_viewport(x, y));
+
+        let cell = t.map(f32::floor);
+        if cell[0] < 0.0 || cell[1] < 0.0 || tiles[0] <= cell[0] || tiles[1] <= cell[1] {
+            return None;
+        }
+        // tiles are drawn around the middle of their cell, smaller by the gap
+        let half = 0.5 * (1.0 - u.gap);
+        if (0..2).any(|i| half < (t[i] - cell[i] - 0.5).abs()) {
+            return None;
+        }
+        Some((cell[0] as u32, cell[1] as u32))
+    }
+
+    /// sets every tile back to how it looked at the start
+    pub fn clear(&mut self) {
+        for viewport in self.viewports.iter_mut() {
+            viewport
+                .instances_color_range
+                .fill(InstanceColorRange::default());
+            viewport
+                .instances_strength
+                .fill(InstanceStrength::default());
+        }
+    }
+
+    /// changes how many tiles there are, the instance buffers are made again so every tile is cleared
+    pub fn set_grid_size(&mut self, w: u32, h: u32) {
+        self.w = w;
+        self.h = h;
+        self.uniform.tiles_x = w;
+        self.uniform.tiles_y = h;
+        self.camera = Camera::fit(&self.uniform);
+        self.set_viewports(self.viewports.len());
+    }
 
-        let index = (tile.x + tile.y * self.w) as usize;
-        let mut i: &mut InstanceColorRange = &mut self.instances_color_range[index];
-        i.high = tile.high.into();
-        i.low = tile.low.into();
-        self.instances_strength[index].value = 0.0;
+    /// the color each tile was last painted in the first viewport, row by row
+    pub fn colors(&self) -> impl Iterator<Item = Color> + '_ {
+        self.viewports[0]
+            .instances_color_range
+            .iter()
+            .map(|v| Color::try_from(v.high).unwrap_or_default())
     }
 
     pub fn tiles_w(&self) -> u32 {
@@ -0,0 +1,139 @@
+// undo and redo for maze edits, with ctrl+z and ctrl+y
+//
+// every edit is a command that knows how to take itself back and do itself again. a drag
+// stroke is one command, however many tiles it went over
+use crate::maze::*;
+
+/// older edits are forgotten past this many bytes. a 1024x1024 maze kept for undoing a new one
+/// is 4 megabytes, so this still undoes about fifteen of those
+const HISTORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// one room, before and after an edit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub tile: (usize, usize),
+    pub before: Room,
+    pub after: Room,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// walls drawn or erased in one stroke
+    SetRooms(Vec<Change>),
+    /// home or goal dragged from one tile to another
+    Move {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    /// a whole new maze. this is the maze that isn't shown, so undo and redo swap them
+    Regenerate(Grid),
+}
+
+impl Command {
+    /// about how much memory the command holds on to
+    fn bytes(&self) -> usize {
+        let held = match self {
+            Self::SetRooms(changes) => changes.capacity() * std::mem::size_of::<Change>(),
+            Self::Move { .. } => 0,
+            Self::Regenerate(other) => other.w() * other.h() * std::mem::size_of::<Room>(),
+        };
+        std::mem::size_of::<Self>() + held
+    }
+
+    fn undo(&mut self, maze: &mut Grid) -> Result<(), String> {
+        match self {
+            Self::SetRooms(changes) => {
+                for change in changes.iter().rev() {
+                    maze.set(change.tile.0, change.tile.1, change.before)?;
+                }
+                Ok(())
+            }
+            Self::Move { from, to } => move_room(maze, *to, *from),
+            Self::Regenerate(other) => {
+                std::mem::swap(maze, other);
+                Ok(())
+            }
+        }
+    }
+
+    fn redo(&mut self, maze: &mut Grid) -> Result<(), String> {
+        match self {
+            Self::SetRooms(changes) => {
+                for change in changes.iter() {
+                    maze.set(change.tile.0, change.tile.1, change.after)?;
+                }
+                Ok(())
+            }
+            Self::Move { from, to } => move_room(maze, *from, *to),
+            Self::Regenerate(other) => {
+                std::mem::swap(maze, other);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// leaves `from` empty
+fn move_room(maze: &mut Grid, from: (usize, usize), to: (usize, usize)) -> Result<(), String> {
+    let room = maze
+        .room(from.0, from.1)
+        .ok_or(format!("({}, {}) is outside of the maze", from.0, from.1))?;
+    maze.set(from.0, from.1, Room::Empty)?;
+    maze.set(to.0, to.1, room)
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    done: Vec<Command>,
+    undone: Vec<Command>,
+}
+
+impl History {
+    /// an edit that was just made. what was undone before it can't be redone anymore
+    pub fn push(&mut self, command: Command) {
+        self.undone.clear();
+        self.done.push(command);
+        // the oldest go first, but the edit just made can always be undone
+        let mut total: usize = self.done.iter().map(Command::bytes).sum();
+        let mut forget = 0;
+        while HISTORY_BYTES < total && forget + 1 < self.done.len() {
+            total -= self.done[forget].bytes();
+            forget += 1;
+        }
+        self.done.drain(..forget);
+    }
+
+    /// takes back the last edit, `Ok(false)` if there is none
+    pub fn undo(&mut self, maze: &mut Grid) -> Result<bool, String> {
+        let mut command = match self.done.pop() {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        if let Err(e) = command.undo(maze) {
+            // the maze isn't what the history thinks it is anymore
+            self.clear();
+            return Err(e);
+        }
+        self.undone.push(command);
+        Ok(true)
+    }
+
+    /// does the last undone edit again, `Ok(false)` if there is none
+    pub fn redo(&mut self, maze: &mut Grid) -> Result<bool, String> {
+        let mut command = match self.undone.pop() {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        if let Err(e) = command.redo(maze) {
+            self.clear();
+            return Err(e);
+        }
+        self.done.push(command);
+        Ok(true)
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
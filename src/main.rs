@@ -1,78 +1,803 @@
 #![allow(unused)]
+use args::Args;
 use cfg_if::cfg_if;
 use color::Color;
 use log::{debug, error, info, trace, warn};
 use maze::*;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use search::*;
 use std::{default::Default, time::Duration};
 use winit::{
-    event::{Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+    event::{
+        ElementState, Event, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
+        VirtualKeyCode, WindowEvent,
+    },
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
 };
 
+mod args;
+mod camera;
 mod color;
+mod font;
+mod generate;
 mod graphics;
+mod history;
 mod maze;
 mod models;
+mod movingai;
+mod netpbm;
+mod scene;
+mod schedule;
 mod search;
+mod share;
+mod svg;
+mod text;
+mod trace;
+mod wfc;
 
 // web uses requestAnimationFrame with default 60 fps
 const FPS_TARGET_NATIVE: u64 = 60;
 const FRAMETIME_TARGET_NATIVE: u64 = 1000 / 60;
 
+// wave function collapse observations per frame, one at a time is too slow to watch on 128x128
+const WFC_STEPS_PER_FRAME: usize = 16;
+
+// smallest and biggest side length of a generated maze, - and + resize between them
+const MAZE_SIZE_MIN: usize = 16;
+const MAZE_SIZE_MAX: usize = 1024;
+
+/// errors unless both sides are from `MAZE_SIZE_MIN` to `MAZE_SIZE_MAX`, for any size that a
+/// maze is generated with
+fn check_size((w, h): (usize, usize)) -> Result<(), String> {
+    if [w, h]
+        .iter()
+        .any(|v| !(MAZE_SIZE_MIN..=MAZE_SIZE_MAX).contains(v))
+    {
+        return Err(format!(
+            "{w}x{h} is too small or too big, sides go from {MAZE_SIZE_MIN} to {MAZE_SIZE_MAX}"
+        ));
+    }
+    Ok(())
+}
+
 // maze maze maze maze
 
-struct MazeTest {
-    rooms: Vec<Room>,
-    w: usize,
-    home: (usize, usize),
-    goal: (usize, usize),
-}
-
-impl Default for MazeTest {
-    fn default() -> Self {
-        let w = 128;
-        let mut maze = Self {
-            rooms: vec![Room::Empty; w * 128],
-            w,
-            home: (2, 12),
-            goal: (10, 12),
+/// the default maze, walls scattered at random, 5000 of them on a 128x128 maze
+fn random_walls(w: usize, h: usize, rng: &mut StdRng) -> Grid {
+    generate::random(w, h, w * h * 5000 / (128 * 128), rng)
+        .unwrap_or_else(|e| panic!("could not scatter random walls: {e}"))
+}
+
+/// picks a maze generator from the first argument, e.g. `cargo run -- cave`
+///
+/// `wfc` is animated, so it comes back as a generator, with a blank maze to show while it runs
+fn maze_from_args(args: &Args, rng: &mut StdRng) -> (Grid, Option<wfc::Wfc>) {
+    let (w, h) = args.size.unwrap_or((128, 128));
+    let maze = match args.get(0) {
+        None | Some("random") => random_walls(w, h, rng),
+        Some("cave") => {
+            generate::cave(w, h, &generate::CaveConfig::default(), rng).unwrap_or_else(|e| {
+                warn!("could not generate cave, falling back to random walls: {e}");
+                random_walls(w, h, rng)
+            })
+        }
+        Some("dungeon") => {
+            // optional corridor style as the second argument
+            let corridor = match args.get(1) {
+                None | Some("lshaped") => generate::Corridor::LShaped,
+                Some("carved") => generate::Corridor::Carved,
+                Some(other) => {
+                    warn!("unknown corridor '{other}', expected lshaped or carved");
+                    generate::Corridor::LShaped
+                }
+            };
+            let config = generate::DungeonConfig {
+                corridor,
+                ..Default::default()
+            };
+            generate::dungeon(w, h, &config, rng).unwrap_or_else(|e| {
+                warn!("could not generate dungeon, falling back to random walls: {e}");
+                random_walls(w, h, rng)
+            })
+        }
+        Some("perfect") => generate::perfect(w, h, rng).unwrap(),
+        Some("braid") => {
+            // optional fraction of dead ends to remove as the second argument
+            let fraction = match args.get(1) {
+                Some(v) => v
+                    .parse()
+                    .unwrap_or_else(|e| panic!("braid fraction '{v}' is not a number: {e}")),
+                None => 0.5,
+            };
+            let mut maze = generate::perfect(w, h, rng).unwrap();
+            generate::braid(&mut maze, fraction, rng);
+            maze
+        }
+        Some("wfc") => {
+            // optional example file as the second argument
+            let example = match args.get(1) {
+                Some(path) => std::fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("could not read wfc example '{path}': {e}")),
+                None => wfc::EXAMPLE.to_string(),
+            };
+            match wfc::Wfc::new(&example, w, h, &wfc::WfcConfig::default()) {
+                Ok(generator) => {
+                    return (Grid::new(w, h), Some(generator));
+                }
+                Err(e) => {
+                    warn!("could not learn from wfc example, falling back to random walls: {e}");
+                    random_walls(w, h, rng)
+                }
+            }
+        }
+        Some(other) => {
+            warn!("unknown maze generator '{other}', expected one of: random, cave, dungeon, perfect, braid, wfc");
+            random_walls(w, h, rng)
+        }
+    };
+    (maze, None)
+}
+
+/// reads a maze file from `--load`, and the seed it was saved with
+///
+/// .map files are moving ai benchmark maps, and .scen files load the map of one of their
+/// scenarios, with its start and goal. .ppm and .pgm images are read with the `--palette`.
+/// anything else is a text maze
+async fn load_maze(path: &str, args: &Args) -> Result<(Grid, Option<u64>), String> {
+    let (maze, seed) = if path.ends_with(".map") {
+        (
+            movingai::parse_map(&read_text(path).await?).map_err(|e| format!("{path}: {e}"))?,
+            None,
+        )
+    } else if path.ends_with(".scen") {
+        let scenarios =
+            movingai::parse_scen(&read_text(path).await?).map_err(|e| format!("{path}: {e}"))?;
+        // the last ones are the longest, and the most fun to watch
+        let i = args.scenario.unwrap_or(scenarios.len());
+        let scenario = scenarios.get(i.wrapping_sub(1)).ok_or(format!(
+            "{path}: there is no scenario {i}, there are {}",
+            scenarios.len()
+        ))?;
+        let map_path = movingai::map_path(path, &scenario.map);
+        let map_path = map_path.to_string_lossy();
+        let mut maze = movingai::parse_map(&read_text(&map_path).await?)
+            .map_err(|e| format!("{map_path}: {e}"))?;
+        let (start, goal) = (scenario.start, scenario.goal);
+        maze.set(start.0, start.1, Room::Home(0))
+            .and_then(|_| maze.set(goal.0, goal.1, Room::Goal(0)))
+            .map_err(|e| format!("{path}: scenario {i}: {e}"))?;
+        (maze, None)
+    } else if [".ppm", ".pgm", ".pnm"].iter().any(|v| path.ends_with(v)) {
+        let palette = match &args.palette {
+            Some(v) => {
+                netpbm::Palette::parse(&read_text(v).await?).map_err(|e| format!("{v}: {e}"))?
+            }
+            None => netpbm::Palette::default(),
         };
+        let bytes = read_bytes(path).await?;
+        (
+            netpbm::parse(&bytes, &palette).map_err(|e| format!("{path}: {e}"))?,
+            None,
+        )
+    } else {
+        text::parse(&read_text(path).await?).map_err(|e| format!("{path}: {e}"))?
+    };
+    // with neither, they are placed once the seed is known
+    if maze.home().is_some() != maze.goal().is_some() {
+        return Err(format!(
+            "{path}: the maze needs both a home, S or H, and a goal, G"
+        ));
+    }
+    Ok((maze, seed))
+}
+
+/// reads a whole file, which the web fetches from next to the page instead
+async fn read_bytes(path: &str) -> Result<Vec<u8>, String> {
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            use wasm_bindgen::JsCast;
+            use wasm_bindgen_futures::JsFuture;
+            let fail = |e| format!("could not fetch '{path}': {e:?}");
+            let window = web_sys::window().ok_or("there is no window to fetch with")?;
+            let response: web_sys::Response = JsFuture::from(window.fetch_with_str(path))
+                .await
+                .map_err(fail)?
+                .dyn_into()
+                .map_err(fail)?;
+            if !response.ok() {
+                return Err(format!("could not fetch '{path}': {}", response.status()));
+            }
+            let buffer = JsFuture::from(response.array_buffer().map_err(fail)?)
+                .await
+                .map_err(fail)?;
+            Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+        } else {
+            std::fs::read(path).map_err(|e| format!("could not read '{path}': {e}"))
+        }
+    }
+}
+
+/// like [`read_bytes`], for text files
+async fn read_text(path: &str) -> Result<String, String> {
+    String::from_utf8(read_bytes(path).await?).map_err(|e| format!("'{path}' is not text: {e}"))
+}
+
+/// writes text to a file. the web can't write files, so it goes to the log there
+fn write_output(path: &str, contents: &str, what: &str) {
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            info!("{what} for {path}:\n{contents}");
+        } else {
+            match std::fs::write(path, contents) {
+                Ok(_) => info!("saved {what} to {path}"),
+                Err(e) => error!("could not save {what} to '{path}': {e}"),
+            }
+        }
+    }
+}
+
+/// writes a recorded search to `--record`, or trace.txt, see [`write_output`]
+fn save_trace(args: &Args, trace: &trace::Trace) {
+    let text = match trace.write() {
+        Ok(v) => v,
+        Err(e) => {
+            error!("could not save trace: {e}");
+            return;
+        }
+    };
+    write_output(
+        args.record.as_deref().unwrap_or("trace.txt"),
+        &text,
+        "trace",
+    );
+}
+
+/// a maze from a shared link, after the # in the url. only the web has links
+fn shared_maze() -> Option<(Grid, String)> {
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            let hash = web_sys::window()
+                .and_then(|win| win.location().hash().ok())
+                .unwrap_or_default();
+            let code = hash.trim_start_matches('#');
+            if code.is_empty() {
+                return None;
+            }
+            match share::decode(code) {
+                Ok(v) => Some(v),
+                Err(e) => {
+                    warn!("could not read the maze in the link: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// puts the maze in the url on the web, so the link shares it
+fn share_maze(maze: &Grid, algorithm: &str) {
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            let code = match share::encode(maze, algorithm) {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("could not put the maze in the link: {e}");
+                    return;
+                }
+            };
+            // replaced, so the back button doesn't go through every maze
+            let history = web_sys::window().and_then(|win| win.history().ok());
+            if let Some(history) = history {
+                let url = format!("#{code}");
+                if let Err(e) =
+                    history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url))
+                {
+                    warn!("could not put the maze in the link: {e:?}");
+                }
+            }
+        }
+    }
+}
+
+/// the generator and size in `args`, so a scene makes the maze they made with `seed`
+fn generator_source(args: &Args, seed: u64) -> scene::Source {
+    scene::Source::Generator {
+        name: args.get(0).unwrap_or("random").to_string(),
+        options: args.positional.iter().skip(1).cloned().collect(),
+        seed,
+        size: args.size.unwrap_or((128, 128)),
+    }
+}
+
+/// reads a scene file from `--scene`
+async fn load_scene(path: &str) -> Result<scene::Scene, String> {
+    let text = read_text(path).await?;
+    let scene = scene::Scene::parse(&text).map_err(|e| format!("{path}: {e}"))?;
+    // generated like `--size` was given
+    if let scene::Source::Generator { size, .. } = scene.maze {
+        check_size(size).map_err(|e| format!("{path}: maze size: {e}"))?;
+    }
+    Ok(scene)
+}
+
+/// writes the scene to `--save-scene`, or scene.ron, see [`write_output`]
+///
+/// `generated` is true while the maze is the one `scene.maze` makes, otherwise the maze itself
+/// is saved
+fn save_scene(
+    args: &Args,
+    scene: &scene::Scene,
+    maze: &Grid,
+    generated: bool,
+    uniform: &graphics::Uniform,
+) {
+    let mut scene = scene.clone();
+    if !generated {
+        scene.maze = match scene::Source::text(maze) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("could not save scene: {e}");
+                return;
+            }
+        };
+        // home and goal are in the maze now
+        scene.home = None;
+        scene.goal = None;
+    }
+    scene.view = scene::View {
+        gap: uniform.gap,
+        margin: uniform.margin,
+        speed: uniform.speed,
+    };
+
+    let text = match scene.write() {
+        Ok(v) => v,
+        Err(e) => {
+            error!("could not save scene: {e}");
+            return;
+        }
+    };
+    write_output(
+        args.save_scene.as_deref().unwrap_or("scene.ron"),
+        &text,
+        "scene",
+    );
+}
+
+/// checks every search against the optimal path lengths in a .scen file, for `--bench`
+#[cfg(not(target_arch = "wasm32"))]
+fn bench(path: &str) -> Result<(), String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("could not read '{path}': {e}"))?;
+    let scenarios = movingai::parse_scen(&text).map_err(|e| format!("{path}: {e}"))?;
+
+    // scenario files usually use one map, but they don't have to
+    let mut maps: Vec<&str> = Vec::new();
+    for scenario in &scenarios {
+        if !maps.contains(&scenario.map.as_str()) {
+            maps.push(&scenario.map);
+        }
+    }
+    for map in maps {
+        let map_path = movingai::map_path(path, map);
+        let text = std::fs::read_to_string(&map_path)
+            .map_err(|e| format!("could not read '{}': {e}", map_path.display()))?;
+        let maze =
+            movingai::parse_map(&text).map_err(|e| format!("{}: {e}", map_path.display()))?;
+        let on_map: Vec<movingai::Scenario> =
+            scenarios.iter().filter(|s| s.map == map).cloned().collect();
+
+        println!(
+            "{map}, {}x{}, {} scenarios",
+            maze.w(),
+            maze.h(),
+            on_map.len()
+        );
+        // the optimal lengths are with diagonal steps √2 long, where octile is the exact distance
+        let options = search::Options {
+            heuristic: search::Heuristic::Octile,
+            connectivity: search::Connectivity::Eight,
+        };
+        for algorithm in &search::ALGORITHMS {
+            let report = movingai::check(algorithm.name, &maze, &on_map, |maze, home| {
+                (algorithm.new)(maze, home, options)
+            });
+            println!("{report}");
+        }
+    }
+    Ok(())
+}
+
+/// writes the maze to `--save`, or maze.txt, see [`write_output`]
+fn save_maze(args: &Args, maze: &Grid, seed: u64) {
+    let text = match text::write(maze, Some(seed)) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("could not save maze: {e}");
+            return;
+        }
+    };
+    write_output(args.save.as_deref().unwrap_or("maze.txt"), &text, "maze");
+}
+
+/// writes what is on screen, search included, as a .ppm image to `--export`, or maze.ppm
+fn export_image(args: &Args, gfx: &graphics::State) {
+    let (w, h) = (gfx.tiles_w() as usize, gfx.tiles_h() as usize);
+    let image = netpbm::write(w, h, gfx.colors());
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            warn!("the web can't save images, {} bytes were thrown away", image.len());
+        } else {
+            let path = args.export.as_deref().unwrap_or("maze.ppm");
+            match std::fs::write(path, image) {
+                Ok(_) => info!("saved image to {path}"),
+                Err(e) => error!("could not save image to '{path}': {e}"),
+            }
+        }
+    }
+}
+
+/// writes the maze and the search so far as an .svg picture to `--svg`, or maze.svg, see
+/// [`write_output`]
+fn export_svg(args: &Args, maze: &Grid, search: &dyn StepSearch, colors: &scene::Colors) {
+    let text = svg::write(maze, search, colors);
+    write_output(args.svg.as_deref().unwrap_or("maze.svg"), &text, "svg");
+}
+
+/// paints what a search did, live or from a trace, so both look the same. races paint each
+/// search in its own viewport
+fn paint_event(
+    gfx: &mut graphics::State,
+    colors: &scene::Colors,
+    viewport: usize,
+    event: &trace::Event,
+) {
+    let (tile, color) = match event {
+        trace::Event::Expanded { tile, .. } => (tile, colors.searched()),
+        trace::Event::Path(tile) => (tile, colors.path()),
+        trace::Event::Found | trace::Event::NoPath => return,
+    };
+    gfx.paint_in(
+        viewport,
+        graphics::Tile {
+            x: tile.0 as u32,
+            y: tile.1 as u32,
+            high: color,
+            ..graphics::Tile::default()
+        },
+    );
+}
 
-        maze.set(maze.goal.0, maze.goal.1, Room::Goal(0));
-        maze.set(maze.home.0, maze.home.1, Room::Home(0));
+fn paint_room(gfx: &mut graphics::State, colors: &scene::Colors, x: usize, y: usize, room: Room) {
+    let color = match colors.room(room) {
+        Some(v) => v,
+        None => return,
+    };
+    const D: f32 = 0.9;
+    let darker = (color.r * D, color.g * D, color.b * D).try_into().unwrap();
+    gfx.paint(graphics::Tile {
+        x: x as u32,
+        y: y as u32,
+        high: color,
+        low: darker,
+        ..graphics::Tile::default()
+    });
+}
+
+fn paint_maze(gfx: &mut graphics::State, colors: &scene::Colors, maze: &Grid) {
+    for ((x, y), room) in maze.iter() {
+        paint_room(gfx, colors, x, y, room);
+    }
+}
 
-        let mut rng = rand::thread_rng();
-        for i in (0..5000) {
-            let r = rng.gen_range(0..maze.rooms.len());
-            match maze.rooms[r] {
-                Room::Empty => maze.rooms[r] = Room::Wall,
-                _ => {}
+/// paints walls, or erases them with `Room::Empty`, on every tile of a line, so a fast drag
+/// doesn't leave holes. home and goal are left alone. every tile that changed goes in `changes`
+fn paint_line(
+    gfx: &mut graphics::State,
+    colors: &scene::Colors,
+    maze: &mut Grid,
+    from: (u32, u32),
+    to: (u32, u32),
+    room: Room,
+    changes: &mut Vec<history::Change>,
+) {
+    // bresenham, stepping one tile at a time along x, y or both
+    let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+    let (dx, dy) = ((to.0 as i64 - x).abs(), -(to.1 as i64 - y).abs());
+    let (sx, sy) = (
+        if x < to.0 as i64 { 1 } else { -1 },
+        if y < to.1 as i64 { 1 } else { -1 },
+    );
+    let mut error = dx + dy;
+    loop {
+        let (ux, uy) = (x as usize, y as usize);
+        let before = maze.room(ux, uy);
+        let paint = match before {
+            Some(Room::Home(_) | Room::Goal(_)) | None => false,
+            Some(old) => old != room,
+        };
+        if paint && maze.set(ux, uy, room).is_ok() {
+            changes.extend(before.map(|before| history::Change {
+                tile: (ux, uy),
+                before,
+                after: room,
+            }));
+            match room {
+                Room::Empty => gfx.unpaint(x as u32, y as u32),
+                _ => paint_room(gfx, colors, ux, uy, room),
             }
         }
+        if (x, y) == (to.0 as i64, to.1 as i64) {
+            break;
+        }
+        let e2 = 2 * error;
+        if dy <= e2 {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
 
-        maze
+/// moves home or goal from one tile to another, if that one is empty
+fn move_marker(
+    gfx: &mut graphics::State,
+    colors: &scene::Colors,
+    maze: &mut Grid,
+    from: (u32, u32),
+    to: (u32, u32),
+) -> bool {
+    let (from, to) = (
+        (from.0 as usize, from.1 as usize),
+        (to.0 as usize, to.1 as usize),
+    );
+    let marker = match maze.room(from.0, from.1) {
+        Some(v @ (Room::Home(_) | Room::Goal(_))) => v,
+        _ => return false,
+    };
+    if maze.room(to.0, to.1) != Some(Room::Empty) {
+        return false;
+    }
+    if maze.set(from.0, from.1, Room::Empty).is_err() || maze.set(to.0, to.1, marker).is_err() {
+        return false;
     }
+    gfx.unpaint(from.0 as u32, from.1 as u32);
+    paint_room(gfx, colors, to.0, to.1, marker);
+    true
+}
+
+/// what a held mouse button does as the cursor moves
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    /// walls, or `Room::Empty` to erase them
+    Paint(Room),
+    /// home or goal, it follows the cursor over empty tiles. `from` is where it was picked up
+    Move { from: (u32, u32) },
+}
+
+/// the search the scene picks, or bfs if there is none by that name
+fn new_search(maze: &Grid, scene: &scene::Scene, name: &str) -> Box<dyn StepSearch> {
+    let algorithm = search::algorithm(name).unwrap_or(&search::ALGORITHMS[0]);
+    let options = search::Options {
+        heuristic: scene.heuristic,
+        connectivity: scene.connectivity,
+    };
+    (algorithm.new)(maze, maze.home().unwrap_or_default(), options)
 }
 
-impl MazeTest {
-    /// panics if out of bounds
-    fn set(&mut self, x: usize, y: usize, value: Room) {
-        self.rooms[y * self.w + x] = value;
+/// the searches with how far they have come, like `bfs: 120 expanded`
+fn title(runs: &[Run], seed: u64) -> String {
+    let runs: Vec<String> = runs
+        .iter()
+        .map(|run| format!("{}: {} expanded", run.name, run.expanded))
+        .collect();
+    format!("A fantastic window! {}, seed: {seed}", runs.join(" | "))
+}
+
+/// what is drawn over the maze: every search in the corner of its viewport, and at the bottom
+/// the tile being inspected, how fast it all goes, and the controls unless they are hidden
+fn draw_hud(
+    gfx: &mut graphics::State,
+    runs: &[Run],
+    replaying: bool,
+    inspected: Option<&str>,
+    status: &str,
+    help: bool,
+) {
+    let [_, line] = gfx.text_cell();
+    let pad = line / 2.0;
+    for (i, run) in runs.iter().enumerate() {
+        let [x, y, _, _] = gfx.viewport_rect(i);
+        if replaying {
+            gfx.text(x + pad, y + pad, "replay", Color::WHITE);
+            continue;
+        }
+        // in steps from home to goal
+        let path = match (run.found, run.done) {
+            (true, _) => run.search.path().len().saturating_sub(1).to_string(),
+            (false, true) => "none".to_string(),
+            (false, false) => "-".to_string(),
+        };
+        let stats = format!(
+            "steps {}  frontier {}  path {path}",
+            run.steps,
+            run.search.frontier_len()
+        );
+        gfx.text(x + pad, y + pad, &run.name, Color::WHITE);
+        gfx.text(x + pad, y + pad + line, &stats, Color::WHITE);
+    }
+
+    let mut lines = Vec::new();
+    lines.extend(inspected.map(|v| (v.to_string(), Color::WHITE)));
+    lines.push((status.to_string(), Color::WHITE));
+    if help {
+        let keys = [
+            "space pause  right step  up/down speed  r restart".to_string(),
+            format!(
+                "1-{} algorithm  n new maze  -/+ size",
+                search::ALGORITHMS.len()
+            ),
+            "left draw walls  right erase  wheel zoom".to_string(),
+            "middle pan  f fit  i inspect  h hide help".to_string(),
+            "ctrl+z undo  ctrl+y redo".to_string(),
+        ];
+        lines.extend(keys.into_iter().map(|v| (v, Color::GREY)));
     }
+    let bottom = gfx.size.height as f32 - pad;
+    let top = bottom - line * lines.len() as f32;
+    for (i, (text, color)) in lines.into_iter().enumerate() {
+        gfx.text(pad, top + line * i as f32, &text, color);
+    }
+}
+
+/// frames per second, counted over half a second at a time so the number can be read
+struct FrameCounter {
+    frames: u32,
+    since: instant::Instant,
+    fps: f64,
 }
 
-impl Maze for MazeTest {
-    fn get(&self, x: isize, y: isize) -> Room {
-        if x < 0 || y < 0 {
-            return Room::Wall;
+impl FrameCounter {
+    fn new() -> Self {
+        Self {
+            frames: 0,
+            since: instant::Instant::now(),
+            fps: 0.0,
         }
-        match self.rooms.get(y as usize * self.w + x as usize) {
-            Some(v) => *v,
-            None => Room::Wall,
+    }
+
+    /// call once per frame
+    fn frame(&mut self) -> f64 {
+        self.frames += 1;
+        let seconds = self.since.elapsed().as_secs_f64();
+        if 0.5 <= seconds {
+            self.fps = self.frames as f64 / seconds;
+            self.frames = 0;
+            self.since = instant::Instant::now();
         }
+        self.fps
+    }
+}
+
+/// everything about one tile for the inspector, and what the search knows about it
+fn describe(maze: &Grid, run: Option<&Run>, tile: (u32, u32)) -> String {
+    let (x, y) = (tile.0 as usize, tile.1 as usize);
+    let room = maze.room(x, y).unwrap_or(Room::Wall);
+    let run = match run {
+        Some(v) => v,
+        None => return format!("({x}, {y}) {room:?}"),
+    };
+    let node = match run.search.node((x, y)) {
+        Some(v) => v,
+        None => return format!("({x}, {y}) {room:?}, not found by {} yet", run.name),
+    };
+    let order = match node.order {
+        Some(v) => format!("expanded as number {}", v + 1),
+        None => "in the frontier".to_string(),
+    };
+    format!(
+        "({x}, {y}) {room:?}, {}: parent {:?}, g {:.2}, h {:.2}, f {:.2}, {order}",
+        run.name, node.parent, node.g, node.h, node.f
+    )
+}
+
+/// one search from home to goal, and its recording
+struct Run {
+    name: String,
+    search: Box<dyn StepSearch>,
+    found: bool,
+    done: bool,
+    /// tiles expanded so far, to compare searches by
+    expanded: usize,
+    /// towards the goal and back home
+    steps: usize,
+    recorder: Option<trace::Recorder>,
+}
+
+/// the searches from `--race`, or the one the scene picks. only the first one is recorded
+fn new_runs(maze: &Grid, scene: &scene::Scene, args: &Args, seed: u64) -> Vec<Run> {
+    let names = match args.race.is_empty() {
+        true => std::slice::from_ref(&scene.algorithm),
+        false => &args.race,
+    };
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let recorder = args
+                .record
+                .as_ref()
+                .filter(|_| i == 0)
+                .map(|_| trace::Recorder::new(maze.clone(), Some(seed)));
+            Run::new(maze, scene, name, recorder)
+        })
+        .collect()
+}
+
+impl Run {
+    /// the blank maze wfc starts with has no home, but the search waits until wfc is done anyway
+    fn new(
+        maze: &Grid,
+        scene: &scene::Scene,
+        name: &str,
+        recorder: Option<trace::Recorder>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            search: new_search(maze, scene, name),
+            found: false,
+            done: false,
+            expanded: 0,
+            steps: 0,
+            recorder,
+        }
+    }
+
+    /// one step towards the goal, or back home once it is found. `None` when there is nothing
+    /// to show, and for every step after the search is done
+    fn step(&mut self, maze: &Grid) -> Option<trace::Event> {
+        if self.done {
+            return None;
+        }
+        self.steps += 1;
+        let event = if !self.found {
+            match self.search.step_goal(maze) {
+                Progress::Searching(tile) => {
+                    self.expanded += 1;
+                    Some(trace::Event::Expanded {
+                        tile,
+                        discovered: self.search.discovered().to_vec(),
+                    })
+                }
+                Progress::Found => {
+                    info!(
+                        "{} found the goal after {} expanded",
+                        self.name, self.expanded
+                    );
+                    self.found = true;
+                    Some(trace::Event::Found)
+                }
+                Progress::NoPath => {
+                    info!("{} found no path from home to goal", self.name);
+                    self.done = true;
+                    Some(trace::Event::NoPath)
+                }
+            }
+        } else {
+            let tile = self.search.step_home();
+            self.done = tile.is_none();
+            tile.map(trace::Event::Path)
+        };
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(event.clone());
+        }
+        event
     }
 }
 // maze maze maze maze
@@ -93,10 +818,104 @@ async fn run() {
             env_logger::init();
         }
     }
+    let mut args = Args::parse().unwrap_or_else(|e| panic!("bad arguments: {e}"));
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = &args.bench {
+        if let Err(e) = bench(path) {
+            error!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
     let event_loop = EventLoop::new();
 
+    // a replay paints a recorded search instead of running one
+    let mut replay = match &args.replay {
+        Some(path) => {
+            let text = read_text(path).await.unwrap_or_else(|e| panic!("{e}"));
+            let trace = trace::Trace::parse(&text).unwrap_or_else(|e| panic!("{path}: {e}"));
+            info!("replaying {path}, {} events", trace.entries.len());
+            Some(trace::Replay::new(trace))
+        }
+        None => None,
+    };
+    let mut scene = match &args.scene {
+        Some(path) => {
+            if let Some(load) = &args.load {
+                warn!("not loading '{load}', the maze comes from the scene '{path}'");
+            }
+            load_scene(path).await.unwrap_or_else(|e| panic!("{e}"))
+        }
+        None => scene::Scene::default(),
+    };
+    let shared = shared_maze();
+    if let Some((_, algorithm)) = &shared {
+        scene.algorithm = algorithm.clone();
+    }
+    let loaded = match &scene.maze {
+        _ if replay.is_some() => replay
+            .as_ref()
+            .map(|v| (v.trace().maze.clone(), v.trace().seed)),
+        _ if shared.is_some() => shared.map(|(maze, _)| (maze, None)),
+        _ if args.scene.is_none() => match &args.load {
+            Some(path) => Some(
+                load_maze(path, &args)
+                    .await
+                    .unwrap_or_else(|e| panic!("{e}")),
+            ),
+            None => None,
+        },
+        scene::Source::Text(_) => scene
+            .text_maze()
+            .unwrap_or_else(|e| panic!("scene maze: {e}"))
+            .map(|maze| (maze, None)),
+        // made just like it was given on the command line
+        scene::Source::Generator {
+            name,
+            options,
+            seed,
+            size,
+        } => {
+            args.positional = std::iter::once(name).chain(options).cloned().collect();
+            args.seed = args.seed.or(Some(*seed));
+            args.size = Some(*size);
+            None
+        }
+    };
+    // a seed that is not given is picked at random, but still shown, so the run can be repeated
+    let mut seed = args
+        .seed
+        .or(loaded.as_ref().and_then(|(_, seed)| *seed))
+        .unwrap_or_else(rand::random);
+    info!("seed: {seed}");
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let names: Vec<&str> = search::ALGORITHMS.iter().map(|v| v.name).collect();
+    if search::algorithm(&scene.algorithm).is_none() {
+        warn!(
+            "unknown algorithm '{}', using bfs. the algorithms are: {}",
+            scene.algorithm,
+            names.join(", ")
+        );
+        scene.algorithm = "bfs".to_string();
+    }
+    args.race.retain(|name| {
+        let known = search::algorithm(name).is_some();
+        if !known {
+            warn!(
+                "unknown algorithm '{name}' left out of the race. the algorithms are: {}",
+                names.join(", ")
+            );
+        }
+        known
+    });
+    // a replay is one search
+    if replay.is_some() {
+        args.race.clear();
+    }
+
     let window = WindowBuilder::new()
-        .with_title("A fantastic window!")
+        .with_title(format!("A fantastic window! seed: {seed}"))
         .build(&event_loop)
         .unwrap();
 
@@ -113,30 +932,65 @@ async fn run() {
             .expect("couldn't append canvas to document body");
     }
 
-    let mut rng = rand::thread_rng();
-
-    let maze = MazeTest::default();
-    let mut gfx = graphics::State::new(window).await;
-    for (i, room) in maze.rooms.iter().enumerate() {
-        let color = match room {
-            Room::Home(_) => Color::RED,
-            Room::Goal(_) => Color::GREEN,
-            Room::Wall => Color::BLUE,
-            _ => continue,
-        };
-        const D: f32 = 0.9;
-        let darker = (color.r * D, color.g * D, color.b * D).try_into().unwrap();
-        gfx.paint(graphics::Tile {
-            x: (i % maze.w) as u32,
-            y: (i / maze.w) as u32,
-            high: color,
-            low: darker,
-            ..graphics::Tile::default()
-        });
+    // the scene is saved with the generator and seed, as long as the maze is the one they make
+    let mut generated = loaded.is_none();
+    if args.scene.is_none() && generated {
+        scene.maze = generator_source(&args, seed);
     }
+    let (mut maze, mut wfc) = match loaded {
+        // benchmark maps come without a home and goal
+        Some((maze, _)) if maze.home().is_none() => {
+            info!("the loaded maze has no home or goal, placing them in its largest open area");
+            let maze = generate::from_largest_region(maze, &mut rng)
+                .unwrap_or_else(|e| panic!("could not place home and goal: {e}"));
+            (maze, None)
+        }
+        Some((maze, _)) => (maze, None),
+        None => maze_from_args(&args, &mut rng),
+    };
+    if wfc.is_none() {
+        scene
+            .place(&mut maze)
+            .unwrap_or_else(|e| panic!("could not move home or goal: {e}"));
+    }
+    if wfc.is_none() {
+        share_maze(&maze, &scene.algorithm);
+    }
+    let mut gfx = graphics::State::new(window, maze.w() as u32, maze.h() as u32).await;
+    let uniform = gfx.get_uniform();
+    uniform.gap = scene.view.gap;
+    uniform.margin = scene.view.margin;
+    uniform.speed = scene.view.speed;
+    let mut runs = new_runs(&maze, &scene, &args, seed);
+    gfx.set_viewports(runs.len());
+    paint_maze(&mut gfx, &scene.colors, &maze);
+    // only set when it changes, the web sets the page title every time
+    let mut shown_title = String::new();
 
-    let mut bfs = search::BFS::new(maze.home);
-    let mut found = false;
+    // in pixels, winit only tells where the cursor is when it moves
+    let mut cursor = (0.0, 0.0);
+    // the button that is held, and what it does
+    let mut drag: Option<(MouseButton, Drag)> = None;
+    let mut last_tile: Option<(u32, u32)> = None;
+    // the middle button pans, apart from the other buttons
+    let mut panning = false;
+    // I logs the tile under the cursor whenever it changes
+    let mut inspecting = false;
+    let mut hovered: Option<(u32, u32)> = None;
+    // ctrl+z and ctrl+y undo and redo edits, a drag stroke at a time
+    let mut history = history::History::default();
+    let mut stroke: Vec<history::Change> = Vec::new();
+    // the maze before a new one was made, kept for undo until wave function collapse is done
+    let mut replaced: Option<Grid> = None;
+    let mut modifiers = ModifiersState::empty();
+    // H hides the controls at the bottom
+    let mut help = true;
+    let mut frame_counter = FrameCounter::new();
+
+    // space pauses, and right arrow steps once while paused
+    let mut paused = false;
+    let mut single_steps = 0;
+    let mut scheduler = schedule::Scheduler::new(args.rate.unwrap_or(schedule::RATE_DEFAULT));
 
     event_loop.run(move |event, _, control_flow| {
         *control_flow = ControlFlow::Poll;
@@ -151,11 +1005,130 @@ async fn run() {
                 event: WindowEvent::CursorMoved { position, .. },
                 ..
             } => {
-                // gfx.get_uniform().mouse = [
-                //     position.x as f32 / gfx.window().inner_size().width as f32,
-                //     position.y as f32 / gfx.window().inner_size().height as f32,
-                //     a,
-                // ];
+                if panning {
+                    gfx.pan(position.x - cursor.0, position.y - cursor.1);
+                }
+                cursor = (position.x, position.y);
+                if inspecting {
+                    let tile = gfx.pick(cursor.0, cursor.1);
+                    if tile != hovered {
+                        hovered = tile;
+                        // a replay has no search to ask
+                        let run = runs
+                            .get(gfx.viewport_at(cursor.0, cursor.1))
+                            .filter(|_| replay.is_none());
+                        if let Some(tile) = tile {
+                            info!("{}", describe(&maze, run, tile));
+                        }
+                    }
+                }
+                let (drag, tile) = match (drag, gfx.pick(cursor.0, cursor.1)) {
+                    (Some((_, drag)), Some(tile)) => (drag, tile),
+                    _ => return,
+                };
+                let from = last_tile.unwrap_or(tile);
+                match drag {
+                    Drag::Paint(room) => {
+                        paint_line(
+                            &mut gfx,
+                            &scene.colors,
+                            &mut maze,
+                            from,
+                            tile,
+                            room,
+                            &mut stroke,
+                        );
+                        last_tile = Some(tile);
+                    }
+                    // stays put over walls, and jumps once the cursor is over an empty tile
+                    Drag::Move { .. } => {
+                        if move_marker(&mut gfx, &scene.colors, &mut maze, from, tile) {
+                            last_tile = Some(tile);
+                        }
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { state, button, .. },
+                ..
+            } => {
+                if button == MouseButton::Middle {
+                    panning = state == ElementState::Pressed;
+                    return;
+                }
+                match state {
+                    // the maze is still being made, or is recorded, while those run
+                    ElementState::Pressed
+                        if drag.is_none() && wfc.is_none() && replay.is_none() =>
+                    {
+                        last_tile = gfx.pick(cursor.0, cursor.1);
+                        let under = last_tile.and_then(|v| maze.room(v.0 as usize, v.1 as usize));
+                        let new = match (button, under, last_tile) {
+                            (
+                                MouseButton::Left,
+                                Some(Room::Home(_) | Room::Goal(_)),
+                                Some(from),
+                            ) => Drag::Move { from },
+                            (MouseButton::Left, _, _) => Drag::Paint(Room::Wall),
+                            (MouseButton::Right, _, _) => Drag::Paint(Room::Empty),
+                            _ => return,
+                        };
+                        stroke.clear();
+                        if let (Drag::Paint(room), Some(tile)) = (new, last_tile) {
+                            paint_line(
+                                &mut gfx,
+                                &scene.colors,
+                                &mut maze,
+                                tile,
+                                tile,
+                                room,
+                                &mut stroke,
+                            );
+                        }
+                        drag = Some((button, new));
+                    }
+                    ElementState::Released if drag.map(|v| v.0) == Some(button) => {
+                        // the whole stroke is undone at once
+                        let command = match (drag.map(|v| v.1), last_tile) {
+                            (Some(Drag::Move { from }), Some(to)) if from != to => {
+                                Some(history::Command::Move {
+                                    from: (from.0 as usize, from.1 as usize),
+                                    to: (to.0 as usize, to.1 as usize),
+                                })
+                            }
+                            (Some(Drag::Paint(_)), _) if !stroke.is_empty() => Some(
+                                history::Command::SetRooms(std::mem::take(&mut stroke)),
+                            ),
+                            _ => None,
+                        };
+                        drag = None;
+                        last_tile = None;
+                        // a click that changed nothing leaves the search be
+                        let command = match command {
+                            Some(v) => v,
+                            None => return,
+                        };
+                        history.push(command);
+                        // the search so far was through the old maze, so it starts over
+                        generated = false;
+                        share_maze(&maze, &scene.algorithm);
+                        gfx.clear();
+                        paint_maze(&mut gfx, &scene.colors, &maze);
+                        runs = new_runs(&maze, &scene, &args, seed);
+                    }
+                    _ => {}
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                // a notch of the wheel is 20 percent, touchpads scroll in pixels
+                let notches = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(v) => v.y as f32 / 50.0,
+                };
+                gfx.zoom(cursor.0, cursor.1, 1.2_f32.powf(notches));
             }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
@@ -175,34 +1148,354 @@ async fn run() {
             } => {
                 *control_flow = ControlFlow::Exit;
             }
-            // Event::WindowEvent {
-            //     event:
-            //         WindowEvent::KeyboardInput {
-            //             input:
-            //                 KeyboardInput {
-            //                     virtual_keycode: Some(VirtualKeyCode::Up),
-            //                     ..
-            //                 },
-            //             ..
-            //         },
-            //     ..
-            // } => {
-            //     gfx.change(0.05);
-            // }
-            // Event::WindowEvent {
-            //     event:
-            //         WindowEvent::KeyboardInput {
-            //             input:
-            //                 KeyboardInput {
-            //                     virtual_keycode: Some(VirtualKeyCode::Down),
-            //                     ..
-            //                 },
-            //             ..
-            //         },
-            //     ..
-            // } => {
-            //     gfx.change(-0.05);
-            // }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode:
+                                    Some(
+                                        key @ (VirtualKeyCode::Minus
+                                        | VirtualKeyCode::NumpadSubtract
+                                        | VirtualKeyCode::Equals
+                                        | VirtualKeyCode::Plus
+                                        | VirtualKeyCode::NumpadAdd
+                                        | VirtualKeyCode::N),
+                                    ),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                // halve or double the maze size, or keep it with N, and make a new maze with the
+                // same generator
+                let (w, h) = match key {
+                    VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => {
+                        (maze.w() / 2, maze.h() / 2)
+                    }
+                    VirtualKeyCode::N => (maze.w(), maze.h()),
+                    _ => (maze.w() * 2, maze.h() * 2),
+                };
+                if let Err(e) = check_size((w, h)) {
+                    debug!("not resizing maze: {e}");
+                    return;
+                }
+
+                // a new seed for every new maze, so the one shown and saved makes this maze
+                seed = rand::random();
+                info!("seed: {seed}");
+                rng = StdRng::seed_from_u64(seed);
+                args.size = Some((w, h));
+                let (new, new_wfc) = maze_from_args(&args, &mut rng);
+                let old = std::mem::replace(&mut maze, new);
+                // a maze wave function collapse didn't finish is not worth going back to
+                if wfc.is_none() {
+                    replaced = Some(old);
+                }
+                wfc = new_wfc;
+                if wfc.is_none() {
+                    if let Some(old) = replaced.take() {
+                        history.push(history::Command::Regenerate(old));
+                    }
+                }
+                // the scene makes the new maze from the new seed, home and goal were for the old one
+                scene.maze = generator_source(&args, seed);
+                generated = true;
+                scene.home = None;
+                scene.goal = None;
+                replay = None;
+                if wfc.is_none() {
+                    share_maze(&maze, &scene.algorithm);
+                }
+                gfx.set_grid_size(maze.w() as u32, maze.h() as u32);
+                paint_maze(&mut gfx, &scene.colors, &maze);
+                runs = new_runs(&maze, &scene, &args, seed);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::S),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                save_maze(&args, &maze, seed);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::P),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                export_image(&args, &gfx);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::V),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                export_svg(&args, &maze, runs[0].search.as_ref(), &scene.colors);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::C),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                save_scene(&args, &scene, &maze, generated, gfx.get_uniform());
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::Space),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                paused = !paused;
+                single_steps = 0;
+                info!("{}", if paused { "paused" } else { "resumed" });
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::Right),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                // stepping pauses, so the step can be looked at
+                paused = true;
+                single_steps += 1;
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode:
+                                    Some(key @ (VirtualKeyCode::Up | VirtualKeyCode::Down)),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                let rate = match key {
+                    VirtualKeyCode::Up => scheduler.rate() * 2.0,
+                    _ => scheduler.rate() / 2.0,
+                };
+                scheduler.set_rate(rate);
+                info!("{} steps per second", scheduler.rate());
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::R),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                // the same maze, searched again from the start
+                if let Some(old) = &replay {
+                    replay = Some(trace::Replay::new(old.trace().clone()));
+                }
+                if wfc.is_none() {
+                    gfx.clear();
+                    paint_maze(&mut gfx, &scene.colors, &maze);
+                    runs = new_runs(&maze, &scene, &args, seed);
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode:
+                                    Some(
+                                        key @ (VirtualKeyCode::Key1
+                                        | VirtualKeyCode::Key2
+                                        | VirtualKeyCode::Key3
+                                        | VirtualKeyCode::Key4
+                                        | VirtualKeyCode::Key5
+                                        | VirtualKeyCode::Key6
+                                        | VirtualKeyCode::Key7
+                                        | VirtualKeyCode::Key8
+                                        | VirtualKeyCode::Key9),
+                                    ),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                // 1 is the first algorithm, and so on
+                let i = key as usize - VirtualKeyCode::Key1 as usize;
+                let algorithm = match search::ALGORITHMS.get(i) {
+                    Some(v) => v,
+                    None => return,
+                };
+                info!("searching with {}", algorithm.name);
+                scene.algorithm = algorithm.name.to_string();
+                // a replay or a race is of other searches, so this one takes over
+                replay = None;
+                args.race.clear();
+                runs = new_runs(&maze, &scene, &args, seed);
+                if gfx.viewports() != 1 {
+                    gfx.set_viewports(1);
+                }
+                if wfc.is_none() {
+                    share_maze(&maze, &scene.algorithm);
+                    gfx.clear();
+                    paint_maze(&mut gfx, &scene.colors, &maze);
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::F),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                gfx.fit_camera();
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::I),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                inspecting = !inspecting;
+                hovered = None;
+                info!(
+                    "inspector {}",
+                    if inspecting { "on, hover a tile" } else { "off" }
+                );
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::H),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                help = !help;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ModifiersChanged(state),
+                ..
+            } => {
+                modifiers = state;
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode:
+                                    Some(key @ (VirtualKeyCode::Z | VirtualKeyCode::Y)),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } if modifiers.ctrl() || modifiers.logo() => {
+                // the maze can't be edited then either
+                if drag.is_some() || wfc.is_some() || replay.is_some() {
+                    return;
+                }
+                // ctrl+shift+z redoes too
+                let redo = key == VirtualKeyCode::Y || modifiers.shift();
+                let (what, done) = match redo {
+                    true => ("redo", history.redo(&mut maze)),
+                    false => ("undo", history.undo(&mut maze)),
+                };
+                match done {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        debug!("nothing to {what}");
+                        return;
+                    }
+                    // part of it may be done, so everything is made again all the same
+                    Err(e) => warn!("could not {what}: {e}"),
+                }
+                // a new maze may be another size
+                if (gfx.tiles_w() as usize, gfx.tiles_h() as usize) != (maze.w(), maze.h()) {
+                    args.size = Some((maze.w(), maze.h()));
+                    gfx.set_grid_size(maze.w() as u32, maze.h() as u32);
+                }
+                generated = false;
+                share_maze(&maze, &scene.algorithm);
+                gfx.clear();
+                paint_maze(&mut gfx, &scene.colors, &maze);
+                runs = new_runs(&maze, &scene, &args, seed);
+            }
             Event::MainEventsCleared => {
                 // use rand::Rng;
                 // let mut rng = rand::thread_rng();
@@ -216,29 +1509,110 @@ async fn run() {
                 //         ..Tile::default()
                 //     })
                 // }
-                if !found {
-                    match bfs.step_goal(&maze) {
-                        Some(v) => gfx.paint(graphics::Tile {
-                            x: v.0 as u32,
-                            y: v.1 as u32,
-                            high: Color::WHITE,
-                            ..graphics::Tile::default()
-                        }),
-                        None => found = true,
-                    };
+                let steps = if paused {
+                    scheduler.reset();
+                    std::mem::take(&mut single_steps)
                 } else {
-                    match bfs.step_home() {
-                        Some(v) => gfx.paint(graphics::Tile {
-                            x: v.0 as u32,
-                            y: v.0 as u32,
-                            high: Color::WHITE,
-                            ..graphics::Tile::default()
-                        }),
-                        None => found = false,
+                    scheduler.steps()
+                };
+                if let Some(replay) = &mut replay {
+                    for _ in 0..steps {
+                        for entry in replay.step() {
+                            paint_event(&mut gfx, &scene.colors, 0, &entry.event);
+                        }
+                    }
+                } else if paused && steps == 0 {
+                    // wave function collapse waits too, but not for the search rate
+                } else if let Some(generator) = &mut wfc {
+                    let mut finished = false;
+                    for _ in 0..WFC_STEPS_PER_FRAME {
+                        match generator.step(&mut rng) {
+                            Ok(wfc::WfcStep::Observed(x, y, room)) => match room {
+                                Room::Empty => gfx.paint(graphics::Tile::new(x as u32, y as u32)),
+                                _ => paint_room(&mut gfx, &scene.colors, x, y, room),
+                            },
+                            Ok(wfc::WfcStep::Restarted) => {
+                                debug!("wave function collapse hit a contradiction, restarting");
+                                gfx.clear();
+                            }
+                            Ok(wfc::WfcStep::Done) => {
+                                maze = generator.maze(&mut rng).unwrap_or_else(|e| {
+                                    warn!("wave function collapse made an unusable maze, falling back to random walls: {e}");
+                                    random_walls(maze.w(), maze.h(), &mut rng)
+                                });
+                                finished = true;
+                                break;
+                            }
+                            Err(e) => {
+                                warn!("{e}, falling back to random walls");
+                                maze = random_walls(maze.w(), maze.h(), &mut rng);
+                                finished = true;
+                                break;
+                            }
+                        }
+                    }
+                    if finished {
+                        wfc = None;
+                        if let Some(old) = replaced.take() {
+                            history.push(history::Command::Regenerate(old));
+                        }
+                        if let Err(e) = scene.place(&mut maze) {
+                            warn!("could not move home or goal: {e}");
+                        }
+                        share_maze(&maze, &scene.algorithm);
+                        gfx.clear();
+                        paint_maze(&mut gfx, &scene.colors, &maze);
+                        runs = new_runs(&maze, &scene, &args, seed);
+                    }
+                } else if drag.is_some() {
+                    // waits for the stroke to end, the search starts over then if it changed the maze
+                } else {
+                    // every search takes a step before any takes the next, so they stay even
+                    for _ in 0..steps {
+                        for (i, run) in runs.iter_mut().enumerate() {
+                            if let Some(event) = run.step(&maze) {
+                                paint_event(&mut gfx, &scene.colors, i, &event);
+                            }
+                        }
+                    }
+                    for run in runs.iter_mut().filter(|v| v.done) {
+                        if let Some(recorder) = run.recorder.take() {
+                            save_trace(&args, recorder.trace());
+                        }
+                    }
+                }
+                if replay.is_none() {
+                    let new_title = title(&runs, seed);
+                    if new_title != shown_title {
+                        gfx.window().set_title(&new_title);
+                        shown_title = new_title;
                     }
                 }
                 // bfs.debug(&mut gfx);
 
+                let fps = frame_counter.frame();
+                let mut status = format!("{fps:.0} fps  {} steps/s", scheduler.rate());
+                if wfc.is_some() {
+                    status.push_str("  making maze");
+                }
+                if paused {
+                    status.push_str("  paused");
+                }
+                let inspected = hovered.filter(|_| inspecting).map(|tile| {
+                    let run = runs
+                        .get(gfx.viewport_at(cursor.0, cursor.1))
+                        .filter(|_| replay.is_none());
+                    describe(&maze, run, tile)
+                });
+                draw_hud(
+                    &mut gfx,
+                    &runs,
+                    replay.is_some(),
+                    inspected.as_deref(),
+                    &status,
+                    help,
+                );
+
                 gfx.update();
                 match gfx.render() {
                     Ok(_) => {}
@@ -2,6 +2,8 @@
 pub enum Room {
     Empty,
     Wall,
+    /// walkable, but with a movement cost, where `Empty` costs 1
+    Terrain(u16),
     Home(u16),
     Goal(u16),
 }
@@ -10,3 +12,143 @@ pub trait Maze {
     /// when out of bounds, return `Room::wall` instead of panicking
     fn get(&self, x: isize, y: isize) -> Room;
 }
+
+/// left, right, up, down
+const NEIGHBORS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// rooms stored row by row, where `(0, 0)` is the top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    rooms: Vec<Room>,
+    w: usize,
+    h: usize,
+}
+
+impl Grid {
+    /// every room is `Room::Empty`
+    pub fn new(w: usize, h: usize) -> Self {
+        Self::filled(w, h, Room::Empty)
+    }
+
+    pub fn filled(w: usize, h: usize, room: Room) -> Self {
+        Self {
+            rooms: vec![room; w * h],
+            w,
+            h,
+        }
+    }
+
+    /// `rooms` is read row by row, and has to be exactly `w * h` long
+    pub fn from_rooms(rooms: Vec<Room>, w: usize, h: usize) -> Result<Self, String> {
+        if rooms.len() != w * h {
+            return Err(format!(
+                "a {w}x{h} grid needs {} rooms, but got {}",
+                w * h,
+                rooms.len()
+            ));
+        }
+        Ok(Self { rooms, w, h })
+    }
+
+    pub fn w(&self) -> usize {
+        self.w
+    }
+
+    pub fn h(&self) -> usize {
+        self.h
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        0 <= x && 0 <= y && x < self.w as isize && y < self.h as isize
+    }
+
+    /// `None` if out of bounds
+    pub fn room(&self, x: usize, y: usize) -> Option<Room> {
+        if self.w <= x || self.h <= y {
+            return None;
+        }
+        Some(self.rooms[y * self.w + x])
+    }
+
+    /// errors if out of bounds
+    pub fn set(&mut self, x: usize, y: usize, room: Room) -> Result<(), String> {
+        if self.w <= x || self.h <= y {
+            return Err(format!(
+                "({x}, {y}) is outside of the {}x{} grid",
+                self.w, self.h
+            ));
+        }
+        self.rooms[y * self.w + x] = room;
+        Ok(())
+    }
+
+    /// every room row by row, with its position
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), Room)> + '_ {
+        self.rooms
+            .iter()
+            .enumerate()
+            .map(|(i, room)| ((i % self.w, i / self.w), *room))
+    }
+
+    /// none if the grid is 0 wide
+    pub fn rows(&self) -> impl Iterator<Item = &[Room]> {
+        self.rooms.chunks(self.w.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[Room]> {
+        self.rows().nth(y)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Room> + '_> {
+        (0..self.w).map(|x| self.column(x))
+    }
+
+    /// empty if out of bounds
+    pub fn column(&self, x: usize) -> impl Iterator<Item = Room> + '_ {
+        self.rooms
+            .iter()
+            .skip(x)
+            .step_by(self.w.max(1))
+            .take(if x < self.w { self.h } else { 0 })
+            .copied()
+    }
+
+    /// the 4 rooms next to `(x, y)` that are inside the grid, walls included
+    pub fn neighbors(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = ((usize, usize), Room)> + '_ {
+        NEIGHBORS
+            .iter()
+            .map(move |(dx, dy)| (x as isize + dx, y as isize + dy))
+            .filter(|&(nx, ny)| self.contains(nx, ny))
+            .map(|(nx, ny)| {
+                let p = (nx as usize, ny as usize);
+                (p, self.rooms[p.1 * self.w + p.0])
+            })
+    }
+
+    /// position of the first `Room::Home`
+    pub fn home(&self) -> Option<(usize, usize)> {
+        self.find(|room| matches!(room, Room::Home(_)))
+    }
+
+    /// position of the first `Room::Goal`
+    pub fn goal(&self) -> Option<(usize, usize)> {
+        self.find(|room| matches!(room, Room::Goal(_)))
+    }
+
+    fn find(&self, f: impl Fn(Room) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, room)| f(*room)).map(|(p, _)| p)
+    }
+}
+
+impl Maze for Grid {
+    fn get(&self, x: isize, y: isize) -> Room {
+        if !self.contains(x, y) {
+            return Room::Wall;
+        }
+        self.rooms[y as usize * self.w + x as usize]
+    }
+}
@@ -0,0 +1,270 @@
+// grid benchmarks from the moving ai lab, see https://movingai.com/benchmarks/formats.html
+//
+// terrain in .map files becomes rooms like this:
+// `.` `G` ground is empty, `S` swamp is terrain with weight 1,
+// and `@` `O` out of bounds, `T` trees and `W` water are walls
+use crate::maze::*;
+use crate::search::*;
+use std::fmt;
+
+/// one line of a .scen file, a search problem on a map
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub bucket: usize,
+    /// the map file, as written in the .scen file
+    pub map: String,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+    /// length of the shortest path, where diagonal steps are √2 long
+    pub optimal: f64,
+}
+
+pub fn parse_map(text: &str) -> Result<Grid, String> {
+    let mut lines = text.lines().enumerate();
+    let (mut w, mut h) = (None, None);
+
+    // header, until the `map` line
+    loop {
+        let (i, line) = lines.next().ok_or("the file ended before the `map` line")?;
+        let n = i + 1;
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("type"), Some(_)) => {}
+            (Some("height"), Some(v)) => {
+                h = Some(
+                    v.parse::<usize>()
+                        .map_err(|e| format!("line {n}: height: {e}"))?,
+                )
+            }
+            (Some("width"), Some(v)) => {
+                w = Some(
+                    v.parse::<usize>()
+                        .map_err(|e| format!("line {n}: width: {e}"))?,
+                )
+            }
+            (Some("map"), None) => break,
+            (None, _) => {}
+            _ => return Err(format!("line {n}: unexpected '{line}' in header")),
+        }
+    }
+
+    let w = w.ok_or("the header has no width")?;
+    let h = h.ok_or("the header has no height")?;
+    // files come from anywhere, so the header is checked before anything is made from it
+    let tiles = w
+        .checked_mul(h)
+        .ok_or(format!("a {w}x{h} map is too big"))?;
+    if crate::MAZE_SIZE_MAX < w || crate::MAZE_SIZE_MAX < h {
+        return Err(format!(
+            "the map is {w}x{h}, but sides can be at most {}",
+            crate::MAZE_SIZE_MAX
+        ));
+    }
+    let rows: Vec<(usize, &str)> = lines.take(h).collect();
+    if rows.len() != h {
+        return Err(format!(
+            "the header says there are {h} rows, but the file ended after {}",
+            rows.len()
+        ));
+    }
+
+    let mut rooms = Vec::with_capacity(tiles);
+    for (i, line) in rows {
+        let n = i + 1;
+        let line = line.trim_end();
+        if line.chars().count() != w {
+            return Err(format!(
+                "line {n}: expected {w} tiles, but got {}",
+                line.chars().count()
+            ));
+        }
+        for (column, c) in line.chars().enumerate() {
+            rooms.push(match c {
+                '.' | 'G' => Room::Empty,
+                'S' => Room::Terrain(1),
+                '@' | 'O' | 'T' | 'W' => Room::Wall,
+                _ => {
+                    return Err(format!(
+                        "line {n}, column {}: unexpected terrain '{c}'",
+                        column + 1
+                    ))
+                }
+            });
+        }
+    }
+
+    Grid::from_rooms(rooms, w, h)
+}
+
+pub fn parse_scen(text: &str) -> Result<Vec<Scenario>, String> {
+    let mut scenarios = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let n = i + 1;
+        if line.trim().is_empty() || line.starts_with("version") {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 9 {
+            return Err(format!(
+                "line {n}: expected 9 fields, but got {}",
+                fields.len()
+            ));
+        }
+        let number = |i: usize| {
+            fields[i]
+                .parse::<usize>()
+                .map_err(|e| format!("line {n}, field {}: {e}", i + 1))
+        };
+        scenarios.push(Scenario {
+            bucket: number(0)?,
+            map: fields[1].to_string(),
+            start: (number(4)?, number(5)?),
+            goal: (number(6)?, number(7)?),
+            optimal: fields[8]
+                .parse()
+                .map_err(|e| format!("line {n}, field 9: {e}"))?,
+        });
+    }
+    Ok(scenarios)
+}
+
+/// the map a .scen file points to. maps usually sit next to their scenarios, so that is tried
+/// before the path as it is written. the web has no files to try, so it gets the path as written
+pub fn map_path(scen_path: &str, map: &str) -> std::path::PathBuf {
+    use std::path::Path;
+    let dir = Path::new(scen_path).parent().unwrap_or(Path::new(""));
+    let beside = dir.join(Path::new(map).file_name().unwrap_or_default());
+    if beside.exists() {
+        return beside;
+    }
+    let relative = dir.join(map);
+    if relative.exists() {
+        return relative;
+    }
+    Path::new(map).to_path_buf()
+}
+
+/// how one algorithm did on a set of scenarios
+#[derive(Debug, Default)]
+pub struct Report {
+    pub name: String,
+    pub total: usize,
+    /// found a path with the optimal length
+    pub optimal: usize,
+    /// scenarios with a longer path than optimal, or no path at all, with why
+    pub failures: Vec<String>,
+    /// sum of path length / optimal length, for an average
+    ratio_sum: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}/{} optimal", self.name, self.optimal, self.total)?;
+        if 0 < self.total {
+            write!(
+                f,
+                ", paths are {:.3}x optimal on average",
+                self.ratio_sum / self.total as f64
+            )?;
+        }
+        // the first few are enough to see what's wrong
+        for failure in self.failures.iter().take(10) {
+            write!(f, "\n    {failure}")?;
+        }
+        if 10 < self.failures.len() {
+            write!(f, "\n    and {} more", self.failures.len() - 10)?;
+        }
+        Ok(())
+    }
+}
+
+/// runs a search from scratch on every scenario, and compares its path with the optimal length
+pub fn check(
+    name: &str,
+    maze: &Grid,
+    scenarios: &[Scenario],
+    new: impl Fn(&Grid, (usize, usize)) -> Box<dyn StepSearch>,
+) -> Report {
+    let mut report = Report {
+        name: name.to_string(),
+        ..Report::default()
+    };
+
+    for (i, scenario) in scenarios.iter().enumerate() {
+        report.total += 1;
+        let length = match path_length(maze, scenario, &new) {
+            Ok(v) => v,
+            Err(e) => {
+                report.failures.push(format!("scenario {}: {e}", i + 1));
+                continue;
+            }
+        };
+
+        // .scen files round to 8 decimals
+        if (length - scenario.optimal).abs() < 1e-4 {
+            report.optimal += 1;
+        } else {
+            report.failures.push(format!(
+                "scenario {}: path from {:?} to {:?} is {length:.4} long, but {:.4} is optimal",
+                i + 1,
+                scenario.start,
+                scenario.goal,
+                scenario.optimal
+            ));
+        }
+        if 0.0 < scenario.optimal {
+            report.ratio_sum += length / scenario.optimal;
+        } else {
+            report.ratio_sum += 1.0;
+        }
+    }
+
+    report
+}
+
+fn path_length(
+    maze: &Grid,
+    scenario: &Scenario,
+    new: impl Fn(&Grid, (usize, usize)) -> Box<dyn StepSearch>,
+) -> Result<f64, String> {
+    let (start, goal) = (scenario.start, scenario.goal);
+    if start == goal {
+        return Ok(0.0);
+    }
+
+    let mut maze = maze.clone();
+    for (p, room) in [(start, Room::Home(0)), (goal, Room::Goal(0))] {
+        match maze.room(p.0, p.1) {
+            Some(Room::Wall) => return Err(format!("{p:?} is inside a wall")),
+            None => return Err(format!("{p:?} is outside of the map")),
+            _ => maze.set(p.0, p.1, room)?,
+        }
+    }
+
+    let mut search = new(&maze, start);
+    loop {
+        match search.step_goal(&maze) {
+            Progress::Searching(_) => {}
+            Progress::Found => break,
+            Progress::NoPath => return Err(format!("found no path from {start:?} to {goal:?}")),
+        }
+    }
+
+    let mut path = vec![goal];
+    while let Some(p) = search.step_home() {
+        path.push(p);
+    }
+    path.push(start);
+
+    Ok(path
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            match (a.0 != b.0, a.1 != b.1) {
+                (true, true) => std::f64::consts::SQRT_2,
+                _ => 1.0,
+            }
+        })
+        .sum())
+}
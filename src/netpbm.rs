@@ -0,0 +1,211 @@
+// ppm and pgm images, so mazes can be drawn in any paint program
+//
+// reads the ascii (P2, P3) and binary (P5, P6) kinds, and writes binary ppm. every pixel is one
+// room, found through a palette, where the closest color wins
+use crate::color::Color;
+use crate::maze::*;
+
+/// which color is which room, several colors can be the same room
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub entries: Vec<([u8; 3], Room)>,
+}
+
+impl Default for Palette {
+    /// black is wall and white is empty, red is home and green is goal. the colors on screen
+    /// are in there too, so an exported maze can be loaded again
+    fn default() -> Self {
+        let mut entries = vec![([0, 0, 0], Room::Wall), ([255, 255, 255], Room::Empty)];
+        let rooms = [Room::Wall, Room::Home(0), Room::Goal(0)]
+            .into_iter()
+            .chain((1..=9).map(Room::Terrain));
+        let colors = crate::scene::Colors::default();
+        for room in rooms {
+            if let Some(color) = colors.room(room) {
+                entries.push((to_bytes(color), room));
+            }
+        }
+        Self { entries }
+    }
+}
+
+impl Palette {
+    /// one color and room per line, like `ff0000 home`, where the room is one of wall, empty,
+    /// home, goal or a terrain weight. `#` starts a comment
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let n = i + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (hex, room) = line.split_once(char::is_whitespace).ok_or(format!(
+                "line {n}: expected a color and a room, like 'ff0000 home'"
+            ))?;
+            let color = u32::from_str_radix(hex, 16)
+                .ok()
+                .filter(|_| hex.len() == 6)
+                .ok_or(format!("line {n}: '{hex}' is not a color like ff0000"))?;
+            let room = match room.trim() {
+                "wall" => Room::Wall,
+                "empty" => Room::Empty,
+                "home" => Room::Home(0),
+                "goal" => Room::Goal(0),
+                weight => Room::Terrain(weight.parse().map_err(|_| {
+                    format!(
+                        "line {n}: '{weight}' is not wall, empty, home, goal or a terrain weight"
+                    )
+                })?),
+            };
+            entries.push(([(color >> 16) as u8, (color >> 8) as u8, color as u8], room));
+        }
+
+        if entries.is_empty() {
+            return Err("the palette has no colors".to_string());
+        }
+        Ok(Self { entries })
+    }
+
+    /// the room with the closest color
+    pub fn room(&self, color: [u8; 3]) -> Room {
+        let distance = |other: &[u8; 3]| -> i32 {
+            (0..3)
+                .map(|i| (color[i] as i32 - other[i] as i32).pow(2))
+                .sum()
+        };
+        self.entries
+            .iter()
+            .min_by_key(|(other, _)| distance(other))
+            .map(|(_, room)| *room)
+            .unwrap_or(Room::Empty)
+    }
+}
+
+/// reads a ppm or pgm image, one room per pixel. grey pixels are looked up as if they were rgb
+pub fn parse(bytes: &[u8], palette: &Palette) -> Result<Grid, String> {
+    let mut reader = Reader { bytes, at: 0 };
+    let magic = reader.word()?;
+    let (channels, binary) = match magic.as_str() {
+        "P2" => (1, false),
+        "P3" => (3, false),
+        "P5" => (1, true),
+        "P6" => (3, true),
+        _ => {
+            return Err(format!(
+                "'{magic}' is not a ppm or pgm image, expected P2, P3, P5 or P6"
+            ))
+        }
+    };
+    let w = reader.number("width")?;
+    let h = reader.number("height")?;
+    let max = reader.number("max value")?;
+    if max == 0 || 65535 < max {
+        return Err(format!("max value {max} has to be from 1 to 65535"));
+    }
+    // one whitespace character comes between the header and binary pixels
+    if binary {
+        reader.at += 1;
+    }
+
+    // the header can say anything, so the pixels have to fit in what is left of the file,
+    // where every sample is at least a byte, or two for binary images with a big max value
+    let samples = w
+        .checked_mul(h)
+        .and_then(|v| v.checked_mul(channels))
+        .ok_or(format!("a {w}x{h} image is too big"))?;
+    let sample_bytes = if binary && 256 <= max { 2 } else { 1 };
+    let left = bytes.len().saturating_sub(reader.at);
+    if left / sample_bytes < samples {
+        return Err(format!(
+            "a {w}x{h} image needs at least {} bytes of pixels, but only {left} are left",
+            samples.saturating_mul(sample_bytes)
+        ));
+    }
+    let mut values = Vec::with_capacity(samples);
+    for i in 0..samples {
+        let value = match (binary, max < 256) {
+            (false, _) => reader.number("pixel")?,
+            (true, true) => reader.bytes(1)?[0] as usize,
+            (true, false) => {
+                let v = reader.bytes(2)?;
+                (v[0] as usize) << 8 | v[1] as usize
+            }
+        };
+        if max < value {
+            return Err(format!(
+                "pixel {} has the value {value}, which is above the max of {max}",
+                i / channels + 1
+            ));
+        }
+        values.push((value * 255 / max) as u8);
+    }
+
+    let rooms = values
+        .chunks(channels)
+        .map(|v| match *v {
+            [grey] => palette.room([grey; 3]),
+            [r, g, b] => palette.room([r, g, b]),
+            _ => unreachable!(),
+        })
+        .collect();
+    Grid::from_rooms(rooms, w, h)
+}
+
+/// a binary ppm image, `colors` row by row
+pub fn write(w: usize, h: usize, colors: impl IntoIterator<Item = Color>) -> Vec<u8> {
+    let mut out = format!("P6\n{w} {h}\n255\n").into_bytes();
+    for color in colors.into_iter().take(w * h) {
+        out.extend(to_bytes(color));
+    }
+    out
+}
+
+fn to_bytes(color: Color) -> [u8; 3] {
+    let rgb: [f32; 3] = color.into();
+    rgb.map(|v| (v * 255.0).round() as u8)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl Reader<'_> {
+    /// the next word in the header, skipping whitespace and `#` comments
+    fn word(&mut self) -> Result<String, String> {
+        loop {
+            match self.bytes.get(self.at) {
+                Some(b'#') => {
+                    while !matches!(self.bytes.get(self.at), Some(b'\n') | None) {
+                        self.at += 1;
+                    }
+                }
+                Some(c) if c.is_ascii_whitespace() => self.at += 1,
+                Some(_) => break,
+                None => return Err("the image ended too early".to_string()),
+            }
+        }
+        let start = self.at;
+        while matches!(self.bytes.get(self.at), Some(c) if !c.is_ascii_whitespace()) {
+            self.at += 1;
+        }
+        Ok(String::from_utf8_lossy(&self.bytes[start..self.at]).into_owned())
+    }
+
+    fn number(&mut self, what: &str) -> Result<usize, String> {
+        let word = self.word()?;
+        word.parse()
+            .map_err(|e| format!("{what} '{word}' is not a number: {e}"))
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&[u8], String> {
+        let v = self
+            .bytes
+            .get(self.at..self.at + n)
+            .ok_or("the image ended too early")?;
+        self.at += n;
+        Ok(v)
+    }
+}
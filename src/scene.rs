@@ -0,0 +1,220 @@
+// scenes are a maze and how it is searched and shown, in one small file, so an exercise looks
+// the same for everyone who opens it
+//
+// they are written in ron, https://github.com/ron-rs/ron, and every field can be left out:
+//
+// (
+//     maze: Generator(name: "cave", seed: 42, size: (64, 32)),
+//     home: Some((3, 4)),
+//     connectivity: Eight,
+//     colors: (wall: (0.2, 0.2, 0.2)),
+//     view: (gap: 0.1),
+// )
+use crate::color::Color;
+use crate::maze::*;
+use crate::search::{Connectivity, Heuristic};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scene {
+    pub maze: Source,
+    /// moves home here, instead of where the maze has it
+    pub home: Option<(usize, usize)>,
+    /// moves the goal here, instead of where the maze has it
+    pub goal: Option<(usize, usize)>,
+    pub algorithm: String,
+    pub heuristic: Heuristic,
+    pub connectivity: Connectivity,
+    pub colors: Colors,
+    pub view: View,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            maze: Source::default(),
+            home: None,
+            goal: None,
+            algorithm: "bfs".to_string(),
+            heuristic: Heuristic::default(),
+            connectivity: Connectivity::default(),
+            colors: Colors::default(),
+            view: View::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Source {
+    /// made by a generator from `cargo run -- <name> <options>`, the same seed makes the same maze
+    Generator {
+        name: String,
+        #[serde(default)]
+        options: Vec<String>,
+        seed: u64,
+        size: (usize, usize),
+    },
+    /// rows of a maze in the format of [`crate::text`]
+    Text(Vec<String>),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Generator {
+            name: "random".to_string(),
+            options: Vec::new(),
+            seed: 0,
+            size: (128, 128),
+        }
+    }
+}
+
+impl Source {
+    /// errors on terrain that the text format can't write
+    pub fn text(maze: &Grid) -> Result<Self, String> {
+        let text = crate::text::write(maze, None)?;
+        // the first line is the size, which the rows already tell
+        Ok(Self::Text(text.lines().skip(1).map(String::from).collect()))
+    }
+}
+
+/// colors are red, green and blue from 0 to 1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    pub wall: [f32; 3],
+    pub home: [f32; 3],
+    pub goal: [f32; 3],
+    /// the lightest terrain, heavier terrain is darker
+    pub terrain: [f32; 3],
+    pub searched: [f32; 3],
+    pub path: [f32; 3],
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self {
+            wall: Color::BLUE.into(),
+            home: Color::RED.into(),
+            goal: Color::GREEN.into(),
+            terrain: [0.6, 0.4, 0.2],
+            searched: Color::WHITE.into(),
+            path: Color::WHITE.into(),
+        }
+    }
+}
+
+impl Colors {
+    /// `None` for empty rooms, they are left as they are
+    pub fn room(&self, room: Room) -> Option<Color> {
+        let rgb = match room {
+            Room::Home(_) => self.home,
+            Room::Goal(_) => self.goal,
+            Room::Wall => self.wall,
+            // heavier terrain is darker
+            Room::Terrain(cost) => {
+                let shade = 1.0 - cost.min(9) as f32 / 10.0;
+                self.terrain.map(|v| v * shade)
+            }
+            Room::Empty => return None,
+        };
+        Some(color(rgb))
+    }
+
+    pub fn searched(&self) -> Color {
+        color(self.searched)
+    }
+
+    pub fn path(&self) -> Color {
+        color(self.path)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let named = [
+            ("wall", self.wall),
+            ("home", self.home),
+            ("goal", self.goal),
+            ("terrain", self.terrain),
+            ("searched", self.searched),
+            ("path", self.path),
+        ];
+        for (name, rgb) in named {
+            Color::try_from(rgb).map_err(|e| format!("{name}: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// colors are checked when a scene is parsed, so this can't fail
+fn color(rgb: [f32; 3]) -> Color {
+    Color::try_from(rgb).unwrap_or_default()
+}
+
+/// how the grid is drawn, see [`crate::graphics::Uniform`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct View {
+    pub gap: f32,
+    pub margin: f32,
+    pub speed: f32,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        let uniform = crate::graphics::Uniform::default();
+        Self {
+            gap: uniform.gap,
+            margin: uniform.margin,
+            speed: uniform.speed,
+        }
+    }
+}
+
+impl Scene {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let scene: Self = ron::from_str(text).map_err(|e| e.to_string())?;
+        scene.colors.check()?;
+        Ok(scene)
+    }
+
+    pub fn write(&self) -> Result<String, String> {
+        let config = ron::ser::PrettyConfig::default().struct_names(false);
+        ron::ser::to_string_pretty(self, config).map_err(|e| e.to_string())
+    }
+
+    /// the maze from [`Scene::maze`], with home and goal where the text has them. [`Scene::place`]
+    /// moves them after
+    ///
+    /// generators need the main loop to make their maze, so those come back as `None`
+    pub fn text_maze(&self) -> Result<Option<Grid>, String> {
+        let rows = match &self.maze {
+            Source::Text(rows) => rows,
+            Source::Generator { .. } => return Ok(None),
+        };
+        let (maze, _) = crate::text::parse(&rows.join("\n"))?;
+        Ok(Some(maze))
+    }
+
+    /// moves home and goal to where the scene says, if it says
+    pub fn place(&self, maze: &mut Grid) -> Result<(), String> {
+        let moves = [
+            (self.home, Room::Home(0), maze.home()),
+            (self.goal, Room::Goal(0), maze.goal()),
+        ];
+        for (to, room, from) in moves {
+            let to = match to {
+                Some(v) => v,
+                None => continue,
+            };
+            if maze.room(to.0, to.1) == Some(Room::Wall) {
+                return Err(format!("{to:?} is inside a wall"));
+            }
+            if let Some(from) = from {
+                maze.set(from.0, from.1, Room::Empty)?;
+            }
+            maze.set(to.0, to.1, room)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+// steps at a fixed rate in real time, however often frames come. the web draws a frame whenever
+// requestAnimationFrame says so, and native sleeps between frames, but both step just as fast
+//
+// every frame asks how many steps are due since the last one. steps that are not whole yet are
+// kept for the next frame, so 0.5 steps per second steps every other second, and 6000 steps per
+// second at 60 fps is 100 steps per frame
+use instant::Instant;
+
+/// steps per second, from a step every other second
+pub const RATE_MIN: f64 = 0.5;
+/// steps per second, some thousands per frame
+pub const RATE_MAX: f64 = 262_144.0;
+pub const RATE_DEFAULT: f64 = 60.0;
+
+/// after a long frame, like when the tab was hidden, only this many seconds of steps are caught
+/// up on, instead of running the whole search at once
+const CATCH_UP_SECONDS: f64 = 0.25;
+
+pub struct Scheduler {
+    rate: f64,
+    last: Instant,
+    /// steps that are due but not taken yet, below 1 after every frame
+    owed: f64,
+}
+
+impl Scheduler {
+    pub fn new(rate: f64) -> Self {
+        Self {
+            rate: rate.clamp(RATE_MIN, RATE_MAX),
+            last: Instant::now(),
+            owed: 0.0,
+        }
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// clamped from [`RATE_MIN`] to [`RATE_MAX`]
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate.clamp(RATE_MIN, RATE_MAX);
+    }
+
+    /// how many steps to take this frame
+    pub fn steps(&mut self) -> usize {
+        let now = Instant::now();
+        let seconds = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+
+        let most = (self.rate * CATCH_UP_SECONDS).max(1.0);
+        self.owed = (self.owed + seconds * self.rate).min(most);
+        let steps = self.owed.floor();
+        self.owed -= steps;
+        steps as usize
+    }
+
+    /// forgets the time since the last frame, so being paused isn't caught up on
+    pub fn reset(&mut self) {
+        self.last = Instant::now();
+        self.owed = 0.0;
+    }
+}
@@ -1,8 +1,11 @@
 use crate::maze::*;
-use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 
-pub const UP: (i8, i8) = (0, 1);
-pub const DOWN: (i8, i8) = (0, -1);
+// y grows downwards, row 0 is at the top of the screen
+pub const UP: (i8, i8) = (0, -1);
+pub const DOWN: (i8, i8) = (0, 1);
 pub const RIGHT: (i8, i8) = (1, 0);
 pub const LEFT: (i8, i8) = (-1, 0);
 
@@ -53,10 +56,151 @@ impl TryFrom<(i8, i8)> for Direction {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    /// expanded this tile, and is still looking
+    Searching((usize, usize)),
+    /// found the goal, walk back with `step_home`
+    Found,
+    /// every reachable tile is expanded, and the goal was not one of them
+    NoPath,
+}
+
+/// which neighbors a search can step to
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Connectivity {
+    /// left, right, up and down
+    #[default]
+    Four,
+    /// the diagonals too, but never past the corner of a wall, like the moving ai benchmarks
+    Eight,
+}
+
+impl Connectivity {
+    pub fn directions(self) -> &'static [(i8, i8)] {
+        match self {
+            Self::Four => &[UP, DOWN, RIGHT, LEFT],
+            Self::Eight => &[UP, DOWN, RIGHT, LEFT, (1, -1), (1, 1), (-1, 1), (-1, -1)],
+        }
+    }
+}
+
+/// guesses how far it is to the goal, for searches that use one
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Heuristic {
+    /// steps when walking without diagonals
+    #[default]
+    Manhattan,
+    /// straight line
+    Euclidean,
+    /// steps when diagonals are √2 long
+    Octile,
+    /// steps when diagonals are as long as the others
+    Chebyshev,
+    /// no guess at all, which makes a* the same as dijkstra
+    Zero,
+}
+
+impl Heuristic {
+    pub fn estimate(self, from: (usize, usize), to: (usize, usize)) -> f64 {
+        let dx = from.0.abs_diff(to.0) as f64;
+        let dy = from.1.abs_diff(to.1) as f64;
+        match self {
+            Self::Manhattan => dx + dy,
+            Self::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Self::Octile => dx.max(dy) + (std::f64::consts::SQRT_2 - 1.0) * dx.min(dy),
+            Self::Chebyshev => dx.max(dy),
+            Self::Zero => 0.0,
+        }
+    }
+}
+
 pub trait StepSearch {
-    fn step_goal<T: Maze>(&mut self, maze: &T) -> Option<(usize, usize)>;
+    fn step_goal(&mut self, maze: &dyn Maze) -> Progress;
 
+    /// walks the path back from the goal, one tile at a time, home and goal not included
     fn step_home(&mut self) -> Option<(usize, usize)>;
+
+    /// tiles that are done, every neighbor has been looked at
+    fn expanded(&self) -> Vec<(usize, usize)>;
+
+    /// tiles that are found, but not expanded yet
+    fn frontier(&self) -> Vec<(usize, usize)>;
+
+    /// how many tiles are in the frontier, without collecting them
+    fn frontier_len(&self) -> usize {
+        self.frontier().len()
+    }
+
+    /// tiles the last step found, or found a shorter way to. they are in the frontier now
+    fn discovered(&self) -> &[(usize, usize)];
+
+    /// from home to goal, both included, empty until the goal is found
+    fn path(&self) -> Vec<(usize, usize)>;
+
+    /// what the search knows about a tile, `None` until it is found
+    fn node(&self, tile: (usize, usize)) -> Option<Node>;
+}
+
+/// one tile as a search sees it, to see why it was expanded when it was
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Node {
+    /// home is its own parent
+    pub parent: (usize, usize),
+    /// cost from home
+    pub g: f64,
+    /// the guess of the cost to the goal, 0 for searches that don't guess
+    pub h: f64,
+    /// what the search sorts the frontier by, lowest first
+    pub f: f64,
+    /// how many tiles were expanded before this one, `None` while it is in the frontier
+    pub order: Option<usize>,
+}
+
+/// what every search is made with, besides the maze and home
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options {
+    pub heuristic: Heuristic,
+    pub connectivity: Connectivity,
+}
+
+/// makes a search from the maze and home
+pub type NewSearch = fn(&Grid, (usize, usize), Options) -> Box<dyn StepSearch>;
+
+/// a search that can be picked by name, like in a scene or with the number keys
+pub struct Algorithm {
+    pub name: &'static str,
+    pub new: NewSearch,
+}
+
+/// every search there is, the first one is the default
+pub const ALGORITHMS: [Algorithm; 5] = [
+    Algorithm {
+        name: "bfs",
+        new: |_, home, options| Box::new(BFS::with_connectivity(home, options.connectivity)),
+    },
+    Algorithm {
+        name: "dfs",
+        new: |_, home, options| Box::new(BFS::depth_first(home, options.connectivity)),
+    },
+    Algorithm {
+        name: "dijkstra",
+        new: |maze, home, options| Box::new(BestFirst::new(maze, home, Priority::Cost, options)),
+    },
+    Algorithm {
+        name: "astar",
+        new: |maze, home, options| {
+            Box::new(BestFirst::new(maze, home, Priority::CostAndGuess, options))
+        },
+    },
+    Algorithm {
+        name: "greedy",
+        new: |maze, home, options| Box::new(BestFirst::new(maze, home, Priority::Guess, options)),
+    },
+];
+
+pub fn algorithm(name: &str) -> Option<&'static Algorithm> {
+    ALGORITHMS.iter().find(|v| v.name == name)
 }
 
 pub struct BFS {
@@ -64,15 +208,42 @@ pub struct BFS {
     edges: VecDeque<(usize, usize)>,
     current: (usize, usize),
     home: (usize, usize),
+    /// the goal, and the tile it was found from
+    found: Option<((usize, usize), (usize, usize))>,
+    connectivity: Connectivity,
+    /// new tiles found by the last step
+    discovered: Vec<(usize, usize)>,
+    /// takes the newest tile from the frontier instead of the oldest
+    depth_first: bool,
+    /// when each expanded tile was expanded
+    order: HashMap<(usize, usize), usize>,
 }
 
 impl BFS {
     pub fn new(home: (usize, usize)) -> Self {
+        Self::with_connectivity(home, Connectivity::Four)
+    }
+
+    pub fn with_connectivity(home: (usize, usize), connectivity: Connectivity) -> Self {
         Self {
-            current: (0, 0),
-            searched: HashMap::new(),
+            current: home,
+            // home is its own parent, so it is never searched again
+            searched: HashMap::from([(home, home)]),
             edges: VecDeque::from([home]),
             home,
+            found: None,
+            connectivity,
+            discovered: Vec::new(),
+            depth_first: false,
+            order: HashMap::new(),
+        }
+    }
+
+    /// the same search, but it goes as deep as it can before it tries anything else
+    pub fn depth_first(home: (usize, usize), connectivity: Connectivity) -> Self {
+        Self {
+            depth_first: true,
+            ..Self::with_connectivity(home, connectivity)
         }
     }
 
@@ -91,42 +262,327 @@ impl BFS {
 }
 
 impl StepSearch for BFS {
-    fn step_goal<T: Maze>(&mut self, maze: &T) -> Option<(usize, usize)> {
-        let e = match self.edges.pop_front() {
+    fn step_goal(&mut self, maze: &dyn Maze) -> Progress {
+        let next = match self.depth_first {
+            true => self.edges.pop_back(),
+            false => self.edges.pop_front(),
+        };
+        let e = match next {
             Some(v) => v,
-            None => panic!("no path found"),
+            None => return Progress::NoPath,
         };
-        for n in [UP, DOWN, RIGHT, LEFT]
-            .into_iter()
-            .map(|v| (e.0 as isize + v.0 as isize, e.1 as isize + v.1 as isize))
-        {
+        self.order.insert(e, self.order.len());
+        self.discovered.clear();
+        let (x, y) = (e.0 as isize, e.1 as isize);
+        for &(dx, dy) in self.connectivity.directions() {
+            let n = (x + dx as isize, y + dy as isize);
             if self.searched.contains_key(&(n.0 as usize, n.1 as usize)) {
                 continue;
             }
+            // no squeezing diagonally past a wall
+            if dx != 0
+                && dy != 0
+                && (maze.get(n.0, y) == Room::Wall || maze.get(x, n.1) == Room::Wall)
+            {
+                continue;
+            }
 
             match maze.get(n.0, n.1) {
-                Room::Empty => {
+                Room::Empty | Room::Terrain(_) => {
                     self.searched.insert((n.0 as usize, n.1 as usize), e);
                     self.edges.push_back((n.0 as usize, n.1 as usize));
+                    self.discovered.push((n.0 as usize, n.1 as usize));
                 }
                 Room::Goal(_) => {
                     self.current = e;
-                    return None;
+                    self.found = Some(((n.0 as usize, n.1 as usize), e));
+                    return Progress::Found;
                 }
                 Room::Home(_) | Room::Wall => {}
             }
         }
 
-        Some(e)
+        Progress::Searching(e)
     }
 
     fn step_home(&mut self) -> Option<(usize, usize)> {
-        let child = self.searched[&self.current];
-        if child == self.home {
+        if self.current == self.home {
             return None;
         }
 
-        self.current = child;
-        Some(self.current)
+        let child = self.current;
+        self.current = self.searched[&child];
+        Some(child)
+    }
+
+    fn expanded(&self) -> Vec<(usize, usize)> {
+        self.order.keys().copied().collect()
+    }
+
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        self.edges.iter().copied().collect()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn discovered(&self) -> &[(usize, usize)] {
+        &self.discovered
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        let (goal, mut current) = match self.found {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        let mut path = vec![goal, current];
+        while current != self.home {
+            current = self.searched[&current];
+            path.push(current);
+        }
+        path.reverse();
+        path
+    }
+
+    /// breadth first expands tiles in the order of how many steps they are from home, so that
+    /// is both g and f. depth first doesn't sort by anything, but gets the same numbers
+    fn node(&self, tile: (usize, usize)) -> Option<Node> {
+        let parent = *self.searched.get(&tile)?;
+        let mut g = 0.0;
+        let mut current = tile;
+        while current != self.home {
+            let next = self.searched[&current];
+            g += step_length(current, next);
+            current = next;
+        }
+        Some(Node {
+            parent,
+            g,
+            h: 0.0,
+            f: g,
+            order: self.order.get(&tile).copied(),
+        })
+    }
+}
+
+/// √2 for diagonal neighbors, 1 for the others
+fn step_length(a: (usize, usize), b: (usize, usize)) -> f64 {
+    match (a.0 != b.0, a.1 != b.1) {
+        (true, true) => std::f64::consts::SQRT_2,
+        _ => 1.0,
+    }
+}
+
+/// which tile a best first search expands next, the one where this is lowest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Priority {
+    /// cost from home, dijkstra
+    Cost,
+    /// cost from home plus the guess of the rest, a*
+    CostAndGuess,
+    /// only the guess, greedy best first
+    Guess,
+}
+
+/// dijkstra, a* and greedy best first, which only differ in what they expand first.
+/// terrain costs its weight to walk into, and diagonals cost √2 times as much
+pub struct BestFirst {
+    priority: Priority,
+    options: Options,
+    home: (usize, usize),
+    /// the guess is towards here
+    goal: (usize, usize),
+    /// the tile each tile was reached from, and the cost from home that way
+    parents: HashMap<(usize, usize), ((usize, usize), f64)>,
+    open: BinaryHeap<Open>,
+    /// expanded tiles, and when they were
+    closed: HashMap<(usize, usize), usize>,
+    /// walks back home once the goal is found
+    current: Option<(usize, usize)>,
+    found: Option<(usize, usize)>,
+    discovered: Vec<(usize, usize)>,
+    /// how many tiles were put in `open`, so ties go to the oldest
+    pushed: usize,
+}
+
+/// a tile waiting in the frontier. tiles found a shorter way to are pushed again, and the old
+/// entry is skipped once it comes up
+#[derive(Debug)]
+struct Open {
+    priority: f64,
+    order: usize,
+    tile: (usize, usize),
+}
+
+impl Ord for Open {
+    /// backwards, so the heap pops the lowest
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then(other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl BestFirst {
+    pub fn new(maze: &Grid, home: (usize, usize), priority: Priority, options: Options) -> Self {
+        let mut search = Self {
+            priority,
+            options,
+            home,
+            goal: maze.goal().unwrap_or_default(),
+            parents: HashMap::from([(home, (home, 0.0))]),
+            open: BinaryHeap::new(),
+            closed: HashMap::new(),
+            current: None,
+            found: None,
+            discovered: Vec::new(),
+            pushed: 0,
+        };
+        search.push(home, 0.0);
+        search
+    }
+
+    /// the guess and the priority of a tile
+    fn rank(&self, tile: (usize, usize), cost: f64) -> (f64, f64) {
+        let guess = match self.priority {
+            Priority::Cost => 0.0,
+            _ => self.options.heuristic.estimate(tile, self.goal),
+        };
+        let priority = match self.priority {
+            Priority::Cost => cost,
+            Priority::CostAndGuess => cost + guess,
+            Priority::Guess => guess,
+        };
+        (guess, priority)
+    }
+
+    fn push(&mut self, tile: (usize, usize), cost: f64) {
+        let (_, priority) = self.rank(tile, cost);
+        self.open.push(Open {
+            priority,
+            order: self.pushed,
+            tile,
+        });
+        self.pushed += 1;
+    }
+}
+
+impl StepSearch for BestFirst {
+    fn step_goal(&mut self, maze: &dyn Maze) -> Progress {
+        let tile = loop {
+            match self.open.pop() {
+                Some(v) if !self.closed.contains_key(&v.tile) => break v.tile,
+                Some(_) => continue,
+                None => return Progress::NoPath,
+            }
+        };
+        self.closed.insert(tile, self.closed.len());
+        self.discovered.clear();
+        let (x, y) = (tile.0 as isize, tile.1 as isize);
+        if let Room::Goal(_) = maze.get(x, y) {
+            self.found = Some(tile);
+            self.current = Some(self.parents[&tile].0);
+            return Progress::Found;
+        }
+
+        let cost = self.parents[&tile].1;
+        for &(dx, dy) in self.options.connectivity.directions() {
+            let n = (x + dx as isize, y + dy as isize);
+            let diagonal = dx != 0 && dy != 0;
+            // no squeezing diagonally past a wall
+            if diagonal && (maze.get(n.0, y) == Room::Wall || maze.get(x, n.1) == Room::Wall) {
+                continue;
+            }
+            let weight = match maze.get(n.0, n.1) {
+                Room::Empty | Room::Goal(_) => 1.0,
+                Room::Terrain(weight) => weight as f64,
+                Room::Home(_) | Room::Wall => continue,
+            };
+            let n = (n.0 as usize, n.1 as usize);
+            let cost = match diagonal {
+                true => cost + weight * std::f64::consts::SQRT_2,
+                false => cost + weight,
+            };
+            let shorter = match self.parents.get(&n) {
+                Some(&(_, old)) => cost < old,
+                None => true,
+            };
+            if shorter && !self.closed.contains_key(&n) {
+                self.parents.insert(n, (tile, cost));
+                self.push(n, cost);
+                self.discovered.push(n);
+            }
+        }
+
+        Progress::Searching(tile)
+    }
+
+    fn step_home(&mut self) -> Option<(usize, usize)> {
+        let child = self.current.filter(|&v| v != self.home)?;
+        self.current = Some(self.parents[&child].0);
+        Some(child)
+    }
+
+    fn expanded(&self) -> Vec<(usize, usize)> {
+        self.closed.keys().copied().collect()
+    }
+
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        self.parents
+            .keys()
+            .copied()
+            .filter(|p| !self.closed.contains_key(p))
+            .collect()
+    }
+
+    fn frontier_len(&self) -> usize {
+        // every closed tile has a parent
+        self.parents.len() - self.closed.len()
+    }
+
+    fn discovered(&self) -> &[(usize, usize)] {
+        &self.discovered
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        let mut current = match self.found {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        let mut path = vec![current];
+        while current != self.home {
+            current = self.parents[&current].0;
+            path.push(current);
+        }
+        path.reverse();
+        path
+    }
+
+    fn node(&self, tile: (usize, usize)) -> Option<Node> {
+        let &(parent, g) = self.parents.get(&tile)?;
+        let (h, f) = self.rank(tile, g);
+        Some(Node {
+            parent,
+            g,
+            h,
+            f,
+            order: self.closed.get(&tile).copied(),
+        })
     }
 }
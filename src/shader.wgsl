@@ -20,12 +20,15 @@ struct InstanceColorRange{
 }
 
 struct UniformTest{
+    view_proj: mat4x4<f32>,
     tiles_x: u32,
+    tiles_y: u32,
     gap: f32,
     margin: f32,
     speed: f32,
     mouse_speed: f32,
     mouse: vec2f,
+    screen: vec2f,
 }
 
 // struct Settings{
@@ -54,26 +57,13 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
     
-    let nx = f32(uniform_test.tiles_x);
-    // let nx = 4.0;
-    let col = f32(index % u32(nx));
-    let row = f32(index / u32(nx));
-    let x = model.position.x;
-    let y = model.position.y;
-    
-    // let w = 2.0 / f32(nx);
-    // out.clip_position.x = 
-    //     x * 2.0 * (1.0 / nx) + 
-    //     row * 2.0 * (1.0 / nx) - 
-    //     (1.0 / nx) * 2.0 * (nx - 1.0) * 0.5;
-    // out.clip_position.x = 
-        // y * 2.0 * (1.0 / nx) + 
-        // col * 2.0 * (1.0 / nx) - 
-        // (1.0 / nx) * 2.0 * (nx - 1.0) * 0.5;
+    let col = f32(index % uniform_test.tiles_x);
+    let row = f32(index / uniform_test.tiles_x);
 
-    out.clip_position.x = (x + row - (nx - 1.0) * 0.5) * 2.0 / nx;
-    out.clip_position.y = (y + col - (nx - 1.0) * 0.5) * 2.0 / nx;
-    out.clip_position.w = 1.0;
+    // in tiles, row 0 is at the top. the camera fits it to the viewport, so tiles stay square
+    // whatever the grid and window size is
+    let tile = vec2(col, row) + 0.5 + model.position.xy * (1.0 - uniform_test.gap);
+    out.clip_position = uniform_test.view_proj * vec4(tile, 0.0, 1.0);
     
     // FIXME: low and high has opposite meaning in my code
     // FIXME: white to grey transition turns black
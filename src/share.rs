@@ -0,0 +1,195 @@
+// mazes packed into a short url safe string, so a link to the web build can carry one, like
+// `index.html#AQcEAQEFAgNiZnMB...`
+//
+// the bytes are a version, the size, home, goal and algorithm, and then the rooms row by row as
+// runs of the same room. numbers are written 7 bits at a time, so small ones take one byte.
+// the bytes are then written as base64url, without padding
+use crate::maze::*;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+const VERSION: u8 = 1;
+
+// the first byte of a run
+const EMPTY: u8 = 0;
+const WALL: u8 = 1;
+const TERRAIN: u8 = 2;
+
+/// errors if there is no home or goal
+pub fn encode(maze: &Grid, algorithm: &str) -> Result<String, String> {
+    let home = maze.home().ok_or("the maze has no home")?;
+    let goal = maze.goal().ok_or("the maze has no goal")?;
+
+    let mut bytes = vec![VERSION];
+    for v in [maze.w(), maze.h(), home.0, home.1, goal.0, goal.1] {
+        number(&mut bytes, v);
+    }
+    number(&mut bytes, algorithm.len());
+    bytes.extend(algorithm.as_bytes());
+
+    // home and goal are already written, so they are empty here
+    let rooms = maze.iter().map(|(_, room)| match room {
+        Room::Home(_) | Room::Goal(_) => Room::Empty,
+        room => room,
+    });
+    let mut run: Option<(Room, usize)> = None;
+    for room in rooms {
+        run = match run {
+            Some((same, length)) if same == room => Some((same, length + 1)),
+            Some((other, length)) => {
+                write_run(&mut bytes, other, length);
+                Some((room, 1))
+            }
+            None => Some((room, 1)),
+        };
+    }
+    if let Some((room, length)) = run {
+        write_run(&mut bytes, room, length);
+    }
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// the maze and the algorithm it was shared with
+pub fn decode(text: &str) -> Result<(Grid, String), String> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(text.trim())
+        .map_err(|e| format!("not a shared maze: {e}"))?;
+    let mut reader = Reader {
+        bytes: &bytes,
+        at: 0,
+    };
+
+    let version = reader.byte()?;
+    if version != VERSION {
+        return Err(format!(
+            "shared maze is version {version}, but only version {VERSION} can be read"
+        ));
+    }
+    let (w, h) = (reader.number()?, reader.number()?);
+    let home = (reader.number()?, reader.number()?);
+    let goal = (reader.number()?, reader.number()?);
+    let length = reader.number()?;
+    let algorithm = String::from_utf8(reader.bytes(length)?.to_vec())
+        .map_err(|e| format!("algorithm is not text: {e}"))?;
+    // the goal would take the place of home
+    if home == goal {
+        return Err(format!("home and goal are both at {home:?}"));
+    }
+
+    // links come from anywhere, so nothing too big to make
+    if w == 0 || h == 0 || crate::MAZE_SIZE_MAX < w || crate::MAZE_SIZE_MAX < h {
+        return Err(format!(
+            "shared maze is {w}x{h}, but sides have to be from 1 to {}",
+            crate::MAZE_SIZE_MAX
+        ));
+    }
+    let tiles = w * h;
+    let mut rooms = Vec::new();
+    while rooms.len() < tiles {
+        let room = match reader.byte()? {
+            EMPTY => Room::Empty,
+            WALL => Room::Wall,
+            TERRAIN => Room::Terrain(
+                u16::try_from(reader.number()?).map_err(|_| "terrain weight is too big")?,
+            ),
+            other => return Err(format!("unknown room {other}")),
+        };
+        let length = reader.number()?;
+        if tiles - rooms.len() < length {
+            return Err("there are more rooms than fit in the maze".to_string());
+        }
+        rooms.resize(rooms.len() + length, room);
+    }
+
+    let mut maze = Grid::from_rooms(rooms, w, h)?;
+    maze.set(home.0, home.1, Room::Home(0))?;
+    maze.set(goal.0, goal.1, Room::Goal(0))?;
+    Ok((maze, algorithm))
+}
+
+fn write_run(bytes: &mut Vec<u8>, room: Room, length: usize) {
+    match room {
+        Room::Wall => bytes.push(WALL),
+        Room::Terrain(cost) => {
+            bytes.push(TERRAIN);
+            number(bytes, cost as usize);
+        }
+        _ => bytes.push(EMPTY),
+    }
+    number(bytes, length);
+}
+
+/// 7 bits at a time, the top bit is set while more bytes follow
+fn number(bytes: &mut Vec<u8>, mut v: usize) {
+    while 0x80 <= v {
+        bytes.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&[u8], String> {
+        let v = self
+            .bytes
+            .get(self.at..self.at.saturating_add(n))
+            .ok_or("the shared maze ended too early")?;
+        self.at += n;
+        Ok(v)
+    }
+
+    fn number(&mut self) -> Result<usize, String> {
+        let mut v = 0;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            v |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err("a number in the shared maze is too big".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut maze = Grid::new(300, 5);
+        for x in 0..300 {
+            maze.set(x, 0, Room::Wall).unwrap();
+        }
+        maze.set(7, 2, Room::Terrain(3)).unwrap();
+        maze.set(8, 2, Room::Terrain(1000)).unwrap();
+        maze.set(1, 1, Room::Home(0)).unwrap();
+        maze.set(299, 4, Room::Goal(0)).unwrap();
+
+        let text = encode(&maze, "astar").unwrap();
+        assert_eq!(decode(&text), Ok((maze, "astar".to_string())));
+    }
+
+    #[test]
+    fn home_on_goal() {
+        let mut bytes = vec![VERSION];
+        for v in [2, 1, 0, 0, 0, 0, 0] {
+            number(&mut bytes, v);
+        }
+        bytes.extend([EMPTY, 2]);
+        let text = URL_SAFE_NO_PAD.encode(bytes);
+        assert_eq!(
+            decode(&text),
+            Err("home and goal are both at (0, 0)".to_string())
+        );
+    }
+}
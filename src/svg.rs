@@ -0,0 +1,130 @@
+// svg pictures of a maze and its search, sharp at any size, for slides and such
+//
+// one tile is one unit, and every layer is its own group, so they can be hidden or restyled
+// in an svg editor: background, terrain, walls, expanded, frontier, path, home and goal
+use crate::color::Color;
+use crate::maze::*;
+use crate::scene::Colors;
+use crate::search::StepSearch;
+use std::fmt::Write;
+
+/// how big a tile is when the svg is opened as is, it scales without blur anyway
+const TILE_SIZE: usize = 8;
+
+pub fn write(maze: &Grid, search: &dyn StepSearch, colors: &Colors) -> String {
+    let (w, h) = (maze.w(), maze.h());
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{}" height="{}" shape-rendering="crispEdges">"#,
+        w * TILE_SIZE,
+        h * TILE_SIZE
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        r#"<rect id="background" width="{w}" height="{h}" fill="{}"/>"#,
+        hex(background())
+    )
+    .unwrap();
+
+    // terrain has a color for each weight, so it is styled tile by tile
+    out.push_str("<g id=\"terrain\">\n");
+    for ((x, y), room) in maze.iter() {
+        if let (Room::Terrain(_), Some(color)) = (room, colors.room(room)) {
+            writeln!(
+                out,
+                r#"  <rect x="{x}" y="{y}" width="1" height="1" fill="{}"/>"#,
+                hex(color)
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("</g>\n");
+
+    let walls = maze
+        .iter()
+        .filter(|(_, room)| *room == Room::Wall)
+        .map(|(p, _)| p);
+    group(&mut out, "walls", colors.room(Room::Wall).unwrap(), walls);
+    // sorted, so the same search always makes the same file
+    let mut expanded = search.expanded();
+    expanded.sort_by_key(|&(x, y)| (y, x));
+    group(&mut out, "expanded", colors.searched(), expanded);
+    let mut frontier = search.frontier();
+    frontier.sort_by_key(|&(x, y)| (y, x));
+    // the screen doesn't paint the frontier, but a figure needs to show it
+    group(&mut out, "frontier", frontier_color(), frontier);
+
+    // a line through the middle of the tiles reads better than filled tiles. the path is often
+    // the same color as the expanded tiles under it, so it has a dark outline
+    let path = search.path();
+    if !path.is_empty() {
+        let points: Vec<String> = path
+            .iter()
+            .map(|(x, y)| format!("{}.5,{}.5", x, y))
+            .collect();
+        let points = points.join(" ");
+        out.push_str(r#"<g id="path" fill="none" stroke-linecap="round" stroke-linejoin="round">"#);
+        out.push('\n');
+        writeln!(
+            out,
+            r#"  <polyline points="{points}" stroke="{}" stroke-width="0.5"/>"#,
+            hex(Color::BLACK)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r#"  <polyline points="{points}" stroke="{}" stroke-width="0.25"/>"#,
+            hex(colors.path())
+        )
+        .unwrap();
+        out.push_str("</g>\n");
+    }
+
+    group(
+        &mut out,
+        "home",
+        colors.room(Room::Home(0)).unwrap(),
+        maze.home(),
+    );
+    group(
+        &mut out,
+        "goal",
+        colors.room(Room::Goal(0)).unwrap(),
+        maze.goal(),
+    );
+    out.push_str("</svg>\n");
+    out
+}
+
+/// tiles that are not painted on screen are light grey
+fn background() -> Color {
+    Color::new(0.9, 0.9, 0.9).unwrap()
+}
+
+/// yellow, so it stands out from the background, the expanded tiles and terrain
+fn frontier_color() -> Color {
+    Color::new(1.0, 0.8, 0.2).unwrap()
+}
+
+fn group(
+    out: &mut String,
+    id: &str,
+    color: Color,
+    tiles: impl IntoIterator<Item = (usize, usize)>,
+) {
+    writeln!(out, r#"<g id="{id}" fill="{}">"#, hex(color)).unwrap();
+    for (x, y) in tiles {
+        writeln!(out, r#"  <rect x="{x}" y="{y}" width="1" height="1"/>"#).unwrap();
+    }
+    out.push_str("</g>\n");
+}
+
+/// like `#ff0000`
+fn hex(color: Color) -> String {
+    let rgb: [f32; 3] = color.into();
+    let [r, g, b] = rgb.map(|v| (v * 255.0).round() as u8);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
@@ -0,0 +1,139 @@
+// plain text mazes, one character per room:
+//
+// size 8x4
+// seed 42
+// ########
+// #S..2..#
+// #.##9#G#
+// ########
+//
+// `#` wall, `.` empty, `S` or `H` home, `G` goal, and `1` to `9` terrain weights.
+// there can be one home and one goal at most. the `size` and `seed` lines at the top are optional
+use crate::maze::*;
+
+/// reads a maze and the seed it was made with, if the header has one
+pub fn parse(text: &str) -> Result<(Grid, Option<u64>), String> {
+    let mut size: Option<(usize, usize)> = None;
+    let mut seed = None;
+    let mut rooms = Vec::new();
+    let mut w = None;
+    let mut h = 0;
+    // line and column of each, searches only look for one
+    let mut home: Option<(usize, usize)> = None;
+    let mut goal: Option<(usize, usize)> = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let n = i + 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+
+        if w.is_none() {
+            if let Some(value) = line.strip_prefix("size ") {
+                size = Some(
+                    value
+                        .trim()
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .ok_or(format!("line {n}: size '{value}' is not like 40x20"))?,
+                );
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("seed ") {
+                seed = Some(
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|e| format!("line {n}: seed '{value}' is not a number: {e}"))?,
+                );
+                continue;
+            }
+        }
+
+        for (column, c) in line.chars().enumerate() {
+            let seen = match c {
+                'S' | 'H' => Some(("home", &mut home)),
+                'G' => Some(("goal", &mut goal)),
+                _ => None,
+            };
+            if let Some((name, seen)) = seen {
+                if let Some((first_n, first_column)) = *seen {
+                    return Err(format!(
+                        "line {n}, column {}: a second {name}, \
+                        the first is at line {first_n}, column {first_column}",
+                        column + 1
+                    ));
+                }
+                *seen = Some((n, column + 1));
+            }
+            rooms.push(match c {
+                '#' => Room::Wall,
+                '.' => Room::Empty,
+                'S' | 'H' => Room::Home(0),
+                'G' => Room::Goal(0),
+                '1'..='9' => Room::Terrain(c.to_digit(10).unwrap() as u16),
+                _ => {
+                    return Err(format!(
+                        "line {n}, column {}: unexpected '{c}', expected one of # . S H G or 1-9",
+                        column + 1
+                    ))
+                }
+            });
+        }
+
+        let len = line.chars().count();
+        match w {
+            None => w = Some(len),
+            Some(w) if w != len => {
+                return Err(format!(
+                    "line {n}: every row has to be the same length, expected {w} but got {len}"
+                ))
+            }
+            _ => {}
+        }
+        h += 1;
+    }
+
+    let w = w.ok_or("there is no maze, only a header")?;
+    if let Some((sw, sh)) = size {
+        if (sw, sh) != (w, h) {
+            return Err(format!(
+                "header says the maze is {sw}x{sh}, but it is {w}x{h}"
+            ));
+        }
+    }
+
+    let grid = Grid::from_rooms(rooms, w, h)?;
+    Ok((grid, seed))
+}
+
+/// writes a maze with a header, errors on terrain weights that don't fit in one digit
+pub fn write(grid: &Grid, seed: Option<u64>) -> Result<String, String> {
+    let mut out = format!("size {}x{}\n", grid.w(), grid.h());
+    if let Some(seed) = seed {
+        out += &format!("seed {seed}\n");
+    }
+    let header = out.lines().count();
+
+    for (y, row) in grid.rows().enumerate() {
+        for (x, room) in row.iter().enumerate() {
+            out.push(match *room {
+                Room::Wall => '#',
+                Room::Empty => '.',
+                Room::Home(_) => 'S',
+                Room::Goal(_) => 'G',
+                Room::Terrain(cost @ 1..=9) => char::from_digit(cost as u32, 10).unwrap(),
+                Room::Terrain(cost) => {
+                    return Err(format!(
+                        "line {}, column {}: terrain weight {cost} does not fit in one digit",
+                        header + y + 1,
+                        x + 1
+                    ))
+                }
+            });
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
@@ -0,0 +1,65 @@
+// text on top of the tiles, one instance per character, from the atlas in font.rs
+
+struct Glyph {
+    // top left corner, in pixels from the top left of the window
+    @location(0) position: vec2f,
+    // screen pixels per font pixel
+    @location(1) scale: f32,
+    // cell in the atlas, row by row
+    @location(2) index: u32,
+    @location(3) color: vec3f,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2f,
+    @location(1) color: vec3f,
+}
+
+struct Screen {
+    // the window, in pixels
+    size: vec2f,
+    // font::CELL, font pixels of one character
+    cell: vec2f,
+}
+
+@group(0) @binding(0)
+var<uniform> screen: Screen;
+@group(0) @binding(1)
+var atlas: texture_2d<f32>;
+@group(0) @binding(2)
+var atlas_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32, glyph: Glyph) -> VertexOutput {
+    var out: VertexOutput;
+
+    // two triangles covering the cell
+    var corners = array<vec2f, 6>(
+        vec2f(0.0, 0.0), vec2f(0.0, 1.0), vec2f(1.0, 0.0),
+        vec2f(0.0, 1.0), vec2f(1.0, 1.0), vec2f(1.0, 0.0)
+    );
+    let corner = corners[index];
+    let cell = screen.cell;
+    let atlas_size = vec2f(textureDimensions(atlas));
+    let columns = u32(atlas_size.x / cell.x);
+
+    let pixel = glyph.position + corner * cell * glyph.scale;
+    out.clip_position = vec4(
+        pixel.x / screen.size.x * 2.0 - 1.0,
+        1.0 - pixel.y / screen.size.y * 2.0,
+        0.0,
+        1.0
+    );
+    let at = vec2f(f32(glyph.index % columns), f32(glyph.index / columns));
+    out.uv = (at + corner) * cell / atlas_size;
+    out.color = glyph.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // the space around glyphs is black, so text can be read over any tile
+    let ink = textureSample(atlas, atlas_sampler, in.uv).r;
+    return vec4<f32>(in.color * ink, 1.0);
+}
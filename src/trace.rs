@@ -0,0 +1,233 @@
+// recorded searches, to replay a run exactly, on the web too
+//
+// a trace is a maze in the format of [`crate::text`], an `events` line, and then one event per
+// line, each starting with its step and the milliseconds since the search started:
+//
+// 0 0 e 1 1 2 1 1 2
+// 1 16 e 2 1 3 1
+// 9 150 f
+// 10 166 p 4 1
+//
+// `e x y` expanded a tile, followed by the new tiles it found. `p x y` is one step of the path
+// back home, `f` found the goal and `n` means there is no path. steps without events have no
+// lines, but still take their turn when replayed
+//
+// terrain too heavy for one digit is written as 9 in the maze, and then as `terrain x y weight`
+// lines before `events`
+use crate::maze::*;
+use instant::Instant;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Expanded {
+        tile: (usize, usize),
+        discovered: Vec<(usize, usize)>,
+    },
+    Found,
+    NoPath,
+    Path((usize, usize)),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub step: usize,
+    /// since the search started
+    pub ms: u64,
+    pub event: Event,
+}
+
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub maze: Grid,
+    pub seed: Option<u64>,
+    pub entries: Vec<Entry>,
+}
+
+impl Trace {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = text.lines().collect();
+        let at = lines
+            .iter()
+            .position(|v| v.trim() == "events")
+            .ok_or("there is no `events` line after the maze")?;
+        let (heavy, rows): (Vec<&str>, Vec<&str>) =
+            lines[..at].iter().partition(|v| v.starts_with("terrain "));
+        let (mut maze, seed) = crate::text::parse(&rows.join("\n"))?;
+        for line in heavy {
+            let numbers = line
+                .split_whitespace()
+                .skip(1)
+                .map(|v| v.parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("'{line}': {e}"))?;
+            let (x, y, weight) = match numbers[..] {
+                [x, y, weight] => (x, y, weight),
+                _ => return Err(format!("'{line}' is not like `terrain x y weight`")),
+            };
+            let weight = u16::try_from(weight).map_err(|_| format!("'{line}': too heavy"))?;
+            maze.set(x, y, Room::Terrain(weight))
+                .map_err(|e| format!("'{line}': {e}"))?;
+        }
+
+        let mut entries = Vec::new();
+        for (i, line) in lines.iter().enumerate().skip(at + 1) {
+            let n = i + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = entry(line).map_err(|e| format!("line {n}: {e}"))?;
+            // replays go through the steps in order
+            let last = entries.last().map_or(0, |v: &Entry| v.step);
+            if entry.step < last {
+                return Err(format!(
+                    "line {n}: step {} comes after step {last}",
+                    entry.step
+                ));
+            }
+            entries.push(entry);
+        }
+        Ok(Self {
+            maze,
+            seed,
+            entries,
+        })
+    }
+
+    pub fn write(&self) -> Result<String, String> {
+        // text mazes only have one digit for terrain
+        let mut light = self.maze.clone();
+        let mut heavy = Vec::new();
+        for ((x, y), room) in self.maze.iter() {
+            if let Room::Terrain(weight @ 10..) = room {
+                light.set(x, y, Room::Terrain(9))?;
+                heavy.push((x, y, weight));
+            }
+        }
+        let mut out = crate::text::write(&light, self.seed)?;
+        for (x, y, weight) in heavy {
+            writeln!(out, "terrain {x} {y} {weight}").unwrap();
+        }
+        out.push_str("events\n");
+        for entry in &self.entries {
+            write!(out, "{} {} ", entry.step, entry.ms).unwrap();
+            match &entry.event {
+                Event::Expanded { tile, discovered } => {
+                    write!(out, "e {} {}", tile.0, tile.1).unwrap();
+                    for (x, y) in discovered {
+                        write!(out, " {x} {y}").unwrap();
+                    }
+                }
+                Event::Found => out.push('f'),
+                Event::NoPath => out.push('n'),
+                Event::Path((x, y)) => write!(out, "p {x} {y}").unwrap(),
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+fn entry(line: &str) -> Result<Entry, String> {
+    let mut words = line.split_whitespace();
+    let mut number = |what: &str| -> Result<usize, String> {
+        let word = words.next().ok_or(format!("missing {what}"))?;
+        word.parse()
+            .map_err(|e| format!("{what} '{word}' is not a number: {e}"))
+    };
+    let step = number("step")?;
+    let ms = number("milliseconds")? as u64;
+
+    let kind = words.next().ok_or("missing event")?;
+    let numbers = words
+        .map(|v| v.parse().map_err(|e| format!("'{v}' is not a number: {e}")))
+        .collect::<Result<Vec<usize>, String>>()?;
+    if numbers.len() % 2 != 0 {
+        return Err("tiles need both x and y".to_string());
+    }
+    let mut tiles = numbers.chunks(2).map(|v| (v[0], v[1]));
+
+    let event = match (kind, tiles.next()) {
+        ("e", Some(tile)) => Event::Expanded {
+            tile,
+            discovered: tiles.collect(),
+        },
+        ("p", Some(tile)) if tiles.next().is_none() => Event::Path(tile),
+        ("f", None) => Event::Found,
+        ("n", None) => Event::NoPath,
+        _ => return Err(format!("'{line}' is not an event")),
+    };
+    Ok(Entry { step, ms, event })
+}
+
+/// collects events while a search runs
+pub struct Recorder {
+    trace: Trace,
+    start: Instant,
+    step: usize,
+}
+
+impl Recorder {
+    pub fn new(maze: Grid, seed: Option<u64>) -> Self {
+        Self {
+            trace: Trace {
+                maze,
+                seed,
+                entries: Vec::new(),
+            },
+            start: Instant::now(),
+            step: 0,
+        }
+    }
+
+    /// every event of one step, call it once per step even without events
+    pub fn record(&mut self, events: impl IntoIterator<Item = Event>) {
+        let ms = self.start.elapsed().as_millis() as u64;
+        for event in events {
+            self.trace.entries.push(Entry {
+                step: self.step,
+                ms,
+                event,
+            });
+        }
+        self.step += 1;
+    }
+
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+}
+
+/// plays a trace back one step at a time, just like it was recorded
+pub struct Replay {
+    trace: Trace,
+    /// the next step
+    step: usize,
+    /// the first entry of the next step or later
+    next: usize,
+}
+
+impl Replay {
+    pub fn new(trace: Trace) -> Self {
+        Self {
+            trace,
+            step: 0,
+            next: 0,
+        }
+    }
+
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// the events of the next step, empty for steps without any, and once the trace is over
+    pub fn step(&mut self) -> &[Entry] {
+        let entries = &self.trace.entries;
+        let start = self.next;
+        while entries.get(self.next).map(|v| v.step) == Some(self.step) {
+            self.next += 1;
+        }
+        self.step += 1;
+        &entries[start..self.next]
+    }
+}
@@ -0,0 +1,415 @@
+// overlapping model wave function collapse, based on https://github.com/mxgmn/WaveFunctionCollapse
+use crate::maze::*;
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// widest and tallest output. every tile keeps a few bytes for each pattern, which is about
+/// 50 megabytes at this size with the example
+pub const SIZE_MAX: usize = 256;
+
+/// small maze to learn from, where corridors run between wall pillars on every other tile
+#[rustfmt::skip]
+pub const EXAMPLE: &str = "\
+#.###.##
+....#...
+#.#####.
+#...#...
+###.#.##
+..#...#.
+#.###.#.
+....#...
+";
+
+/// left, right, up, down
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+fn opposite(d: usize) -> usize {
+    [1, 0, 3, 2][d]
+}
+
+/// settings for [`Wfc`]
+#[derive(Debug, Clone)]
+pub struct WfcConfig {
+    /// side length of the patterns learned from the example
+    pub n: usize,
+    /// learn patterns that wrap around the edges of the example
+    pub periodic_input: bool,
+    /// also learn rotated and mirrored patterns
+    pub symmetry: bool,
+    /// how many times to start over after a contradiction before giving up
+    pub max_attempts: usize,
+}
+
+impl Default for WfcConfig {
+    fn default() -> Self {
+        Self {
+            n: 3,
+            periodic_input: true,
+            symmetry: true,
+            max_attempts: 10,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WfcStep {
+    /// this tile was observed, and is now known
+    Observed(usize, usize, Room),
+    /// hit a contradiction and started over with an empty wave
+    Restarted,
+    /// every tile is known, see [`Wfc::maze`]
+    Done,
+}
+
+/// wave function collapse that is run one observation at a time, so it can be animated
+///
+/// the output wraps around at the edges
+pub struct Wfc {
+    w: usize,
+    h: usize,
+    max_attempts: usize,
+    attempts: usize,
+    /// every n*n pattern in the example, row by row
+    patterns: Vec<Vec<Room>>,
+    weights: Vec<f64>,
+    /// `propagator[d][p]` are the patterns that can be next to `p` in direction `d`
+    propagator: [Vec<Vec<usize>>; 4],
+    /// `wave[tile * patterns + p]` is true if pattern `p` is still possible at that tile
+    wave: Vec<bool>,
+    /// `compatible[tile * patterns + p][d]` is how many patterns in direction `d` still allow `p`
+    compatible: Vec<[u16; 4]>,
+    /// what `compatible` is for a tile where every pattern is possible
+    all_compatible: Vec<[u16; 4]>,
+    /// possible patterns left at each tile, and their summed weights for entropy
+    counts: Vec<usize>,
+    sums: Vec<f64>,
+    log_sums: Vec<f64>,
+    banned: Vec<(usize, usize)>,
+    /// undecided tiles by entropy. a tile is pushed again when it changes, and the old entry is
+    /// skipped when it comes up
+    queue: BinaryHeap<Candidate>,
+    /// tiles that changed since they were last pushed to `queue`
+    changed: Vec<usize>,
+    is_changed: Vec<bool>,
+}
+
+/// a tile in [`Wfc::queue`], lowest entropy first
+struct Candidate {
+    entropy: f64,
+    tile: usize,
+    /// how many patterns the tile had when it was pushed, it is out of date if that changed
+    count: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the heap pops the biggest, so lower entropy is bigger
+        other.entropy.total_cmp(&self.entropy)
+    }
+}
+
+impl Wfc {
+    /// learns patterns from an example maze in the format of [`crate::text`]
+    pub fn new(example: &str, w: usize, h: usize, config: &WfcConfig) -> Result<Self, String> {
+        let (example, _) = crate::text::parse(example)?;
+        let (iw, ih) = (example.w(), example.h());
+        let input: Vec<Room> = example.iter().map(|(_, room)| room).collect();
+        let n = config.n;
+        if SIZE_MAX < w || SIZE_MAX < h {
+            return Err(format!(
+                "{w}x{h} is too big for wave function collapse, \
+                it can be at most {SIZE_MAX}x{SIZE_MAX}"
+            ));
+        }
+        if n == 0 || iw < n || ih < n {
+            return Err(format!(
+                "pattern size {n} does not fit in the {iw}x{ih} example"
+            ));
+        }
+
+        let mut patterns: Vec<Vec<Room>> = Vec::new();
+        let mut weights: Vec<f64> = Vec::new();
+        let (max_x, max_y) = if config.periodic_input {
+            (iw, ih)
+        } else {
+            (iw - n + 1, ih - n + 1)
+        };
+        for y in 0..max_y {
+            for x in 0..max_x {
+                let pattern: Vec<Room> = (0..n * n)
+                    .map(|i| input[((y + i / n) % ih) * iw + (x + i % n) % iw])
+                    .collect();
+                let variants = if config.symmetry {
+                    symmetries(pattern, n)
+                } else {
+                    vec![pattern]
+                };
+                for variant in variants {
+                    match patterns.iter().position(|p| *p == variant) {
+                        Some(i) => weights[i] += 1.0,
+                        None => {
+                            patterns.push(variant);
+                            weights.push(1.0);
+                        }
+                    }
+                }
+            }
+        }
+
+        let propagator: [Vec<Vec<usize>>; 4] = [0, 1, 2, 3].map(|d| {
+            let (dx, dy) = DIRECTIONS[d];
+            (0..patterns.len())
+                .map(|a| {
+                    (0..patterns.len())
+                        .filter(|&b| agrees(&patterns[a], &patterns[b], dx, dy, n))
+                        .collect()
+                })
+                .collect()
+        });
+
+        // every count fits in a u16 when there aren't more patterns than that
+        if usize::from(u16::MAX) < patterns.len() {
+            return Err(format!(
+                "the example has {} patterns, more than the {} that fit",
+                patterns.len(),
+                u16::MAX
+            ));
+        }
+        let all_compatible = (0..patterns.len())
+            .map(|p| [0, 1, 2, 3].map(|d| propagator[opposite(d)][p].len() as u16))
+            .collect();
+
+        let mut wfc = Self {
+            w,
+            h,
+            max_attempts: config.max_attempts,
+            attempts: 0,
+            patterns,
+            weights,
+            propagator,
+            wave: Vec::new(),
+            compatible: Vec::new(),
+            all_compatible,
+            counts: Vec::new(),
+            sums: Vec::new(),
+            log_sums: Vec::new(),
+            banned: Vec::new(),
+            queue: BinaryHeap::new(),
+            changed: Vec::new(),
+            is_changed: Vec::new(),
+        };
+        wfc.clear();
+        Ok(wfc)
+    }
+
+    /// starts over with every pattern possible everywhere, in the memory of the last try
+    fn clear(&mut self) {
+        let tiles = self.w * self.h;
+        let count = self.patterns.len();
+        let sum: f64 = self.weights.iter().sum();
+        let log_sum: f64 = self.weights.iter().map(|w| w * w.ln()).sum();
+
+        self.wave.clear();
+        self.wave.resize(tiles * count, true);
+        self.compatible.clear();
+        for _ in 0..tiles {
+            self.compatible.extend_from_slice(&self.all_compatible);
+        }
+        self.counts.clear();
+        self.counts.resize(tiles, count);
+        self.sums.clear();
+        self.sums.resize(tiles, sum);
+        self.log_sums.clear();
+        self.log_sums.resize(tiles, log_sum);
+        self.banned.clear();
+
+        // every tile goes in the queue before the first observation
+        self.queue.clear();
+        self.changed.clear();
+        self.changed.extend(0..tiles);
+        self.is_changed.clear();
+        self.is_changed.resize(tiles, true);
+    }
+
+    /// observes one tile and propagates the consequences, errors when out of attempts
+    pub fn step<R: Rng>(&mut self, rng: &mut R) -> Result<WfcStep, String> {
+        let tile = match self.lowest_entropy(rng) {
+            Some(tile) => tile,
+            None => return Ok(WfcStep::Done),
+        };
+
+        // pick one of the remaining patterns, weighted by how often it was in the example
+        let count = self.patterns.len();
+        let mut roll = rng.gen_range(0.0..self.sums[tile]);
+        let mut chosen = 0;
+        for p in 0..count {
+            if !self.wave[tile * count + p] {
+                continue;
+            }
+            chosen = p;
+            if roll < self.weights[p] {
+                break;
+            }
+            roll -= self.weights[p];
+        }
+        for p in 0..count {
+            if self.wave[tile * count + p] && p != chosen {
+                self.ban(tile, p);
+            }
+        }
+
+        if !self.propagate() {
+            return self.restart();
+        }
+        Ok(WfcStep::Observed(
+            tile % self.w,
+            tile / self.w,
+            self.patterns[chosen][0],
+        ))
+    }
+
+    fn restart(&mut self) -> Result<WfcStep, String> {
+        // every start over so far hit one too, and so did the first try
+        if self.max_attempts <= self.attempts {
+            return Err(format!(
+                "wave function collapse hit a contradiction {} times in a row",
+                self.attempts + 1
+            ));
+        }
+        self.attempts += 1;
+        self.clear();
+        Ok(WfcStep::Restarted)
+    }
+
+    /// the undecided tile with the lowest entropy, `None` when every tile is decided.
+    /// contradictions are found by [`Self::propagate`], so no tile is left with nothing here
+    fn lowest_entropy<R: Rng>(&mut self, rng: &mut R) -> Option<usize> {
+        for tile in self.changed.drain(..) {
+            self.is_changed[tile] = false;
+            let count = self.counts[tile];
+            if count < 2 {
+                continue;
+            }
+            let sum = self.sums[tile];
+            // a little noise, so ties are broken randomly
+            let entropy = sum.ln() - self.log_sums[tile] / sum + rng.gen_range(0.0..1e-6);
+            self.queue.push(Candidate {
+                entropy,
+                tile,
+                count,
+            });
+        }
+
+        while let Some(candidate) = self.queue.pop() {
+            let count = self.counts[candidate.tile];
+            if candidate.count == count && 1 < count {
+                return Some(candidate.tile);
+            }
+        }
+        None
+    }
+
+    fn ban(&mut self, tile: usize, p: usize) {
+        let i = tile * self.patterns.len() + p;
+        self.wave[i] = false;
+        self.compatible[i] = [0; 4];
+        self.counts[tile] -= 1;
+        self.sums[tile] -= self.weights[p];
+        self.log_sums[tile] -= self.weights[p] * self.weights[p].ln();
+        self.banned.push((tile, p));
+        if !self.is_changed[tile] {
+            self.is_changed[tile] = true;
+            self.changed.push(tile);
+        }
+    }
+
+    /// returns false on contradiction
+    fn propagate(&mut self) -> bool {
+        let count = self.patterns.len();
+        while let Some((tile, p)) = self.banned.pop() {
+            let (x, y) = ((tile % self.w) as isize, (tile / self.w) as isize);
+            for (d, (dx, dy)) in DIRECTIONS.into_iter().enumerate() {
+                let nx = (x + dx).rem_euclid(self.w as isize) as usize;
+                let ny = (y + dy).rem_euclid(self.h as isize) as usize;
+                let neighbor = ny * self.w + nx;
+                for i in 0..self.propagator[d][p].len() {
+                    let q = self.propagator[d][p][i];
+                    let left = &mut self.compatible[neighbor * count + q][d];
+                    if *left == 0 {
+                        continue;
+                    }
+                    *left -= 1;
+                    if *left == 0 {
+                        self.ban(neighbor, q);
+                        if self.counts[neighbor] == 0 {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// the finished maze, with home and goal placed by [`crate::generate::from_largest_region`]
+    pub fn maze<R: Rng>(&self, rng: &mut R) -> Result<Grid, String> {
+        let rooms = self
+            .wave
+            .chunks(self.patterns.len())
+            .map(|possible| match possible.iter().position(|p| *p) {
+                Some(p) => self.patterns[p][0],
+                None => Room::Wall,
+            })
+            .collect();
+        crate::generate::from_largest_region(Grid::from_rooms(rooms, self.w, self.h)?, rng)
+    }
+}
+
+/// the 8 rotations and reflections of a pattern
+fn symmetries(pattern: Vec<Room>, n: usize) -> Vec<Vec<Room>> {
+    let rotate = |p: &Vec<Room>| -> Vec<Room> {
+        (0..n * n).map(|i| p[(n - 1 - i % n) * n + i / n]).collect()
+    };
+    let reflect = |p: &Vec<Room>| -> Vec<Room> {
+        (0..n * n).map(|i| p[(i / n) * n + n - 1 - i % n]).collect()
+    };
+
+    let mut out = vec![pattern];
+    for i in 1..4 {
+        out.push(rotate(&out[i - 1]));
+    }
+    for i in 0..4 {
+        out.push(reflect(&out[i]));
+    }
+    out
+}
+
+/// true if `b` placed at offset `(dx, dy)` from `a` matches where they overlap
+fn agrees(a: &[Room], b: &[Room], dx: isize, dy: isize, n: usize) -> bool {
+    let n = n as isize;
+    let (x_min, x_max) = if dx < 0 { (0, dx + n) } else { (dx, n) };
+    let (y_min, y_max) = if dy < 0 { (0, dy + n) } else { (dy, n) };
+    for y in y_min..y_max {
+        for x in x_min..x_max {
+            if a[(x + n * y) as usize] != b[(x - dx + n * (y - dy)) as usize] {
+                return false;
+            }
+        }
+    }
+    true
+}